@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+/// Longest lock a maker can put on an escrow, 30 days.
+pub const MAX_LOCK_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// Open offers a single `PairRegistry` can list.
+pub const MAX_PAIR_ENTRIES: usize = 32;
+
+/// Metaplex Token Metadata program, owner of NFT metadata accounts.
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
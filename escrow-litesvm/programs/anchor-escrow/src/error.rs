@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Escrow is still locked. Must wait until the unlock time.")]
+    EscrowStillLocked,
+    #[msg("Lock period must be between zero and the maximum lock period.")]
+    InvalidLockPeriod,
+    #[msg("Expiry must be after the unlock time.")]
+    InvalidExpiry,
+    #[msg("Escrow has expired and can no longer be taken.")]
+    EscrowExpired,
+    #[msg("Escrow asks for a different (or, on a price curve, higher) amount than the taker expected.")]
+    UnexpectedReceive,
+    #[msg("Vault holds less than the taker's minimum.")]
+    VaultBelowMinimum,
+    #[msg("Counter-offer must ask for a non-zero amount different from the escrow's.")]
+    InvalidCounter,
+    #[msg("Mint A is not an NFT (decimals 0, supply 1).")]
+    NotAnNft,
+    #[msg("Metadata account is not valid for mint A.")]
+    InvalidMetadata,
+    #[msg("NFT is not a verified member of the expected collection.")]
+    CollectionMismatch,
+    #[msg("Price curve must decay to a non-zero floor over a non-empty period, starting at `receive`.")]
+    InvalidPriceCurve,
+    #[msg("Remaining accounts must start with `count` (escrow, vault, maker, maker_ata_b) groups for this pair.")]
+    InvalidBatch,
+    #[msg("None of the escrows could be taken within the maximum spend.")]
+    NothingTaken,
+    #[msg("Pair registry has no room for another offer.")]
+    RegistryFull,
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::Discriminator;
+
+use crate::state::PriceCurve;
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscrowMade {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub seed: u64,
+    pub deposit: u64,
+    pub receive: u64,
+    pub unlock_time: i64,
+    pub expires_at: Option<i64>,
+    /// Dutch-auction pricing, `receive` is then the starting price
+    pub curve: Option<PriceCurve>,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscrowTaken {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub seed: u64,
+    /// Mint A sent from the vault to the taker
+    pub amount_a: u64,
+    /// Mint B sent from the taker to the maker
+    pub amount_b: u64,
+    pub unlock_time: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscrowRefunded {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub seed: u64,
+    pub amount: u64,
+    pub unlock_time: i64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterOffered {
+    pub counter: Pubkey,
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub mint_b: Pubkey,
+    /// Mint B deposited, what the maker would receive instead of the escrow's `receive`
+    pub receive: u64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterAccepted {
+    pub counter: Pubkey,
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub seed: u64,
+    /// Mint A sent from the vault to the taker
+    pub amount_a: u64,
+    /// Mint B sent from the counter vault to the maker
+    pub amount_b: u64,
+}
+
+#[event]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterRefunded {
+    pub counter: Pubkey,
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount: u64,
+}
+
+/// Off-chain view of an event emitted by this program via `emit_cpi!`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EscrowView {
+    Made(EscrowMade),
+    Taken(EscrowTaken),
+    Refunded(EscrowRefunded),
+    CounterOffered(CounterOffered),
+    CounterAccepted(CounterAccepted),
+    CounterRefunded(CounterRefunded),
+}
+
+impl EscrowView {
+    /// Decodes the data of a self-CPI emitted by `emit_cpi!`. Returns `None` for
+    /// any other instruction data.
+    pub fn decode(ix_data: &[u8]) -> Option<Self> {
+        let data = ix_data.strip_prefix(EVENT_IX_TAG_LE)?;
+
+        if let Some(mut data) = data.strip_prefix(EscrowMade::DISCRIMINATOR) {
+            return EscrowMade::deserialize(&mut data).ok().map(Self::Made);
+        }
+        if let Some(mut data) = data.strip_prefix(EscrowTaken::DISCRIMINATOR) {
+            return EscrowTaken::deserialize(&mut data).ok().map(Self::Taken);
+        }
+        if let Some(mut data) = data.strip_prefix(EscrowRefunded::DISCRIMINATOR) {
+            return EscrowRefunded::deserialize(&mut data).ok().map(Self::Refunded);
+        }
+        if let Some(mut data) = data.strip_prefix(CounterOffered::DISCRIMINATOR) {
+            return CounterOffered::deserialize(&mut data).ok().map(Self::CounterOffered);
+        }
+        if let Some(mut data) = data.strip_prefix(CounterAccepted::DISCRIMINATOR) {
+            return CounterAccepted::deserialize(&mut data).ok().map(Self::CounterAccepted);
+        }
+        if let Some(mut data) = data.strip_prefix(CounterRefunded::DISCRIMINATOR) {
+            return CounterRefunded::deserialize(&mut data).ok().map(Self::CounterRefunded);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::error::EscrowError;
+use crate::state::{Counter, Escrow};
+use crate::utils::transfer_checked_with_hook;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CounterOffer<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    pub maker: SystemAccount<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = maker,
+        has_one = mint_a,
+        has_one = mint_b,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"counter", escrow.key().as_ref(), taker.key().as_ref()],
+        bump,
+        space = 8 + Counter::INIT_SPACE,
+    )]
+    pub counter: Account<'info, Counter>,
+    #[account(
+        init,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = counter,
+    )]
+    pub counter_vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CounterOffer<'info> {
+    pub fn init_counter(&mut self, receive: u64, bumps: &CounterOfferBumps) -> Result<()> {
+        require!(
+            receive > 0 && receive != self.escrow.receive,
+            EscrowError::InvalidCounter
+        );
+
+        // No point negotiating over an offer that can no longer be taken
+        if let Some(expires_at) = self.escrow.expires_at {
+            require!(Clock::get()?.unix_timestamp < expires_at, EscrowError::EscrowExpired);
+        }
+
+        self.counter.set_inner(Counter {
+            escrow: self.escrow.key(),
+            taker: self.taker.key(),
+            mint_b: self.mint_b.key(),
+            receive,
+            bump: bumps.counter,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.taker_ata_b.to_account_info(),
+            &self.mint_b,
+            self.counter_vault.to_account_info(),
+            self.taker.to_account_info(),
+            remaining_accounts,
+            self.counter.receive,
+            &[],
+        )
+    }
+}
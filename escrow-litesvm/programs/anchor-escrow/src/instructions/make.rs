@@ -1,9 +1,12 @@
 use std::ops::Neg;
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}};
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
 
-use crate::state::Escrow;
+use crate::error::EscrowError;
+use crate::state::{Escrow, PairEntry, PairRegistry, PriceCurve};
+use crate::utils::transfer_checked_with_hook;
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(seed: u64)]
 pub struct Make<'info> {
@@ -25,6 +28,14 @@ pub struct Make<'info> {
         space = 8 + Escrow::INIT_SPACE,
     )]
     pub escrow: Account<'info, Escrow>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"pair", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+        space = 8 + PairRegistry::INIT_SPACE,
+    )]
+    pub registry: Account<'info, PairRegistry>,
     #[account(
         init,
         payer = maker,
@@ -38,37 +49,52 @@ pub struct Make<'info> {
 }
 
 impl<'info> Make<'info> {
-    pub fn init_escrow(&mut self, seed: u64, receive: u64, bumps: &MakeBumps) -> Result<()> {
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        receive: u64,
+        lock_seconds: i64,
+        expires_at: Option<i64>,
+        curve: Option<PriceCurve>,
+        bumps: &MakeBumps,
+    ) -> Result<()> {
+        let unlock_time = Escrow::unlock_time_from(Clock::get()?.unix_timestamp, lock_seconds, expires_at)?;
+
+        if let Some(curve) = curve {
+            curve.validate()?;
+            require_eq!(curve.start_receive, receive, EscrowError::InvalidPriceCurve);
+        }
 
-        let clock = Clock::get()?;
-        
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
             receive,
-            unlock_time: clock.unix_timestamp.checked_add(1800).unwrap(),
+            unlock_time,
+            expires_at,
+            curve,
             bump: bumps.escrow,
         });
 
         Ok(())
     }
 
-    pub fn deposit(&mut self, deposit: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = TransferChecked {
-            from: self.maker_ata_a.to_account_info(),
-            to: self.vault.to_account_info(),
-            authority: self.maker.to_account_info(),
-            mint: self.mint_a.to_account_info(),
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-
-        transfer_checked(cpi_ctx, deposit, self.mint_a.decimals)?;
+    pub fn register(&mut self, deposit: u64, bumps: &MakeBumps) -> Result<()> {
+        let entry = PairEntry::new(self.escrow.key(), &self.escrow, deposit);
+        self.registry.register(self.mint_a.key(), self.mint_b.key(), bumps.registry, entry)
+    }
 
-        Ok(())
+    pub fn deposit(&mut self, deposit: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.maker_ata_a.to_account_info(),
+            &self.mint_a,
+            self.vault.to_account_info(),
+            self.maker.to_account_info(),
+            remaining_accounts,
+            deposit,
+            &[],
+        )
     }
 }
\ No newline at end of file
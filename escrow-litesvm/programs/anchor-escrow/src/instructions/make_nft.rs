@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::constants::TOKEN_METADATA_PROGRAM_ID;
+use crate::error::EscrowError;
+use crate::metadata::MetadataPrefix;
+use crate::state::{Escrow, PairEntry, PairRegistry};
+use crate::utils::transfer_checked_with_hook;
+
+/// Same as `Make`, but mint A must be an NFT from a verified collection.
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeNft<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Metaplex metadata PDA of mint A, parsed in `verify_nft`
+    #[account(
+        seeds = [b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint_a.key().as_ref()],
+        bump,
+        seeds::program = TOKEN_METADATA_PROGRAM_ID,
+        owner = TOKEN_METADATA_PROGRAM_ID @ EscrowError::InvalidMetadata,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+    )]
+    pub maker_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"escrow", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Escrow::INIT_SPACE,
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"pair", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+        space = 8 + PairRegistry::INIT_SPACE,
+    )]
+    pub registry: Box<Account<'info, PairRegistry>>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeNft<'info> {
+    pub fn verify_nft(&self, collection: &Pubkey) -> Result<()> {
+        require!(
+            self.mint_a.decimals == 0 && self.mint_a.supply == 1,
+            EscrowError::NotAnNft
+        );
+
+        let data = self.metadata.try_borrow_data()?;
+        let metadata = MetadataPrefix::parse(&data).ok_or(EscrowError::InvalidMetadata)?;
+        require_keys_eq!(metadata.mint, self.mint_a.key(), EscrowError::InvalidMetadata);
+
+        require!(metadata.in_collection(collection), EscrowError::CollectionMismatch);
+
+        Ok(())
+    }
+
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        receive: u64,
+        lock_seconds: i64,
+        expires_at: Option<i64>,
+        bumps: &MakeNftBumps,
+    ) -> Result<()> {
+        let unlock_time = Escrow::unlock_time_from(Clock::get()?.unix_timestamp, lock_seconds, expires_at)?;
+
+        self.escrow.set_inner(Escrow {
+            seed,
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            receive,
+            unlock_time,
+            expires_at,
+            curve: None,
+            bump: bumps.escrow,
+        });
+
+        let entry = PairEntry::new(self.escrow.key(), &self.escrow, 1);
+        self.registry.register(self.mint_a.key(), self.mint_b.key(), bumps.registry, entry)
+    }
+
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.maker_ata_a.to_account_info(),
+            &self.mint_a,
+            self.vault.to_account_info(),
+            self.maker.to_account_info(),
+            remaining_accounts,
+            1,
+            &[],
+        )
+    }
+}
@@ -1,7 +1,17 @@
 pub mod make;
+pub mod make_nft;
 pub mod refund;
 pub mod take;
+pub mod take_many;
+pub mod counter_offer;
+pub mod accept_counter;
+pub mod refund_counter;
 
 pub use make::*;
+pub use make_nft::*;
 pub use refund::*;
-pub use take::*;
\ No newline at end of file
+pub use take::*;
+pub use take_many::*;
+pub use counter_offer::*;
+pub use accept_counter::*;
+pub use refund_counter::*;
\ No newline at end of file
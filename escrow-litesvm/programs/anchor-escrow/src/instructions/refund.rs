@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account};
 
-use crate::state::Escrow;
+use crate::error::EscrowError;
+use crate::state::{Escrow, PairRegistry};
+use crate::utils::transfer_checked_with_hook;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Refund<'info> {
     #[account(mut)]
@@ -23,18 +26,33 @@ pub struct Refund<'info> {
         bump = escrow.bump,
     )]
     pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        seeds = [b"pair", mint_a.key().as_ref(), escrow.mint_b.as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, PairRegistry>,
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
     )]
-    vault: InterfaceAccount<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
     token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
 
 impl<'info> Refund<'info> {
-    pub fn refund_and_close_vault(&mut self) -> Result<()> {
+    pub fn unregister(&mut self) {
+        self.registry.remove(&self.escrow.key());
+    }
+
+    pub fn refund_and_close_vault(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // The maker can't pull the offer while takers may still be relying on it
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp >= self.escrow.unlock_time, EscrowError::EscrowStillLocked);
+
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"escrow",
             self.maker.key.as_ref(),
@@ -42,18 +60,16 @@ impl<'info> Refund<'info> {
             &[self.escrow.bump]
         ]];
         
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = TransferChecked {
-            from: self.vault.to_account_info(),
-            to: self.maker_ata_a.to_account_info(),
-            mint: self.mint_a.to_account_info(),
-            authority: self.escrow.to_account_info(),
-        };
-
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
-
-        transfer_checked(cpi_context, self.vault.amount, self.mint_a.decimals)?;
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.vault.to_account_info(),
+            &self.mint_a,
+            self.maker_ata_a.to_account_info(),
+            self.escrow.to_account_info(),
+            remaining_accounts,
+            self.vault.amount,
+            &signer_seeds,
+        )?;
 
         let cpi_program = self.token_program.to_account_info();
 
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account};
+
+use crate::state::Counter;
+use crate::utils::transfer_checked_with_hook;
+
+/// Works whether or not the original escrow still exists, so counter-offers
+/// left behind by a take, refund or another accepted counter can be recovered.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundCounter<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = taker,
+        has_one = taker,
+        has_one = mint_b,
+        seeds = [b"counter", counter.escrow.as_ref(), taker.key().as_ref()],
+        bump = counter.bump,
+    )]
+    pub counter: Account<'info, Counter>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = counter,
+    )]
+    pub counter_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundCounter<'info> {
+    pub fn refund_and_close_vault(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"counter",
+            self.counter.escrow.as_ref(),
+            self.taker.key.as_ref(),
+            &[self.counter.bump]
+        ]];
+
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.counter_vault.to_account_info(),
+            &self.mint_b,
+            self.taker_ata_b.to_account_info(),
+            self.counter.to_account_info(),
+            remaining_accounts,
+            self.counter_vault.amount,
+            &signer_seeds,
+        )?;
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = CloseAccount {
+            account: self.counter_vault.to_account_info(),
+            destination: self.taker.to_account_info(),
+            authority: self.counter.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
+
+        close_account(cpi_context)
+    }
+}
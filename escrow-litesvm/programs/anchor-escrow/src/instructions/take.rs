@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked, CloseAccount, close_account}};
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account}};
 
-use crate::state::Escrow;
+use crate::error::EscrowError;
+use crate::state::{Escrow, PairRegistry};
+use crate::utils::transfer_checked_with_hook;
 
 //Create context
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Take<'info> {
     #[account(mut)]
@@ -42,6 +45,12 @@ pub struct Take<'info> {
         bump = escrow.bump,
     )]
     pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        seeds = [b"pair", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, PairRegistry>,
     #[account(
         mut,
         associated_token::mint = mint_a,
@@ -57,28 +66,51 @@ pub struct Take<'info> {
 //Transfer tokens from vault to taker
 //Close vault account
 impl<'info> Take<'info> {
-    pub fn deposit(&mut self) -> Result<()> {
-
+    /// Aborts if the terms differ from what the taker saw when building the
+    /// transaction, returning the mint B amount to pay. On a price curve the
+    /// ask keeps falling, so `expected_receive` is the most the taker will pay.
+    pub fn check_terms(&self, expected_receive: u64, min_give: u64) -> Result<u64> {
         let clock = Clock::get()?;
+        let receive = self.escrow.current_receive(clock.unix_timestamp);
 
-        // Check if 30 minutes have passed
-        require!(
-            clock.unix_timestamp >= self.escrow.unlock_time, ErrorCode::EscrowStillLocked);
-        let cpi_program = self.token_program.to_account_info();
+        if self.escrow.curve.is_some() {
+            require_gte!(expected_receive, receive, EscrowError::UnexpectedReceive);
+        } else {
+            require_eq!(receive, expected_receive, EscrowError::UnexpectedReceive);
+        }
+        require_gte!(self.vault.amount, min_give, EscrowError::VaultBelowMinimum);
 
-        let cpi_accounts = TransferChecked {
-            from: self.taker_ata_b.to_account_info(),
-            to: self.maker_ata_b.to_account_info(),
-            authority: self.taker.to_account_info(),
-            mint: self.mint_b.to_account_info(),
-        };
+        Ok(receive)
+    }
+
+    pub fn deposit(&mut self, receive: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+
+        let clock = Clock::get()?;
 
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        // Check the lock period has passed and the offer hasn't expired
+        require!(
+            clock.unix_timestamp >= self.escrow.unlock_time, EscrowError::EscrowStillLocked);
+        if let Some(expires_at) = self.escrow.expires_at {
+            require!(clock.unix_timestamp < expires_at, EscrowError::EscrowExpired);
+        }
+
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.taker_ata_b.to_account_info(),
+            &self.mint_b,
+            self.maker_ata_b.to_account_info(),
+            self.taker.to_account_info(),
+            remaining_accounts,
+            receive,
+            &[],
+        )
+    }
 
-        transfer_checked(cpi_ctx, self.escrow.receive, self.mint_b.decimals)
+    pub fn unregister(&mut self) {
+        self.registry.remove(&self.escrow.key());
     }
 
-    pub fn withdraw_and_close_vault(&mut self) -> Result<()> {
+    pub fn withdraw_and_close_vault(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"escrow",
             self.maker.key.as_ref(),
@@ -86,18 +118,16 @@ impl<'info> Take<'info> {
             &[self.escrow.bump]
         ]];
 
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = TransferChecked {
-            from: self.vault.to_account_info(),
-            to: self.taker_ata_a.to_account_info(),
-            authority: self.escrow.to_account_info(),
-            mint: self.mint_a.to_account_info(),
-        };
-
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
-
-        transfer_checked(cpi_context, self.vault.amount, self.mint_a.decimals)?;
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.vault.to_account_info(),
+            &self.mint_a,
+            self.taker_ata_a.to_account_info(),
+            self.escrow.to_account_info(),
+            remaining_accounts,
+            self.vault.amount,
+            &signer_seeds,
+        )?;
 
         let cpi_program = self.token_program.to_account_info();
 
@@ -111,10 +141,4 @@ impl<'info> Take<'info> {
 
         close_account(cpi_context)
     }
-}
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Escrow is still locked. Must wait 30 minutes from creation.")]
-    EscrowStillLocked,
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create},
+    token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account},
+};
+
+use crate::error::EscrowError;
+use crate::events::EscrowTaken;
+use crate::state::{Escrow, PairRegistry};
+use crate::utils::transfer_checked_with_hook;
+
+/// Accounts per escrow in `remaining_accounts`: escrow, vault, maker, maker_ata_b
+pub const TAKE_MANY_GROUP_LEN: usize = 4;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeMany<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+    )]
+    pub taker_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"pair", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PairRegistry>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+//For each (escrow, vault, maker, maker_ata_b) group, in order:
+//Skip it if it's locked, expired or costs more than what's left of `max_spend`
+//Otherwise pay the maker, send the vault to the taker and close both
+impl<'info> TakeMany<'info> {
+    /// The first `count` groups of `remaining_accounts` are the escrows to
+    /// take, anything after them is transfer-hook extra accounts. Returns an
+    /// event for every escrow taken.
+    pub fn take_many(
+        &mut self,
+        count: u8,
+        max_spend: u64,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<EscrowTaken>> {
+        let count = count as usize;
+        require!(
+            count > 0 && remaining_accounts.len() >= count * TAKE_MANY_GROUP_LEN,
+            EscrowError::InvalidBatch
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut remaining = max_spend;
+        let mut taken = Vec::with_capacity(count);
+
+        for group in remaining_accounts[..count * TAKE_MANY_GROUP_LEN].chunks_exact(TAKE_MANY_GROUP_LEN) {
+            let [escrow_info, vault_info, maker, maker_ata_b] = group else {
+                unreachable!();
+            };
+
+            // Only `make` creates accounts owned by this program with the Escrow
+            // discriminator, so the rest only needs to match it
+            let escrow = Account::<Escrow>::try_from(escrow_info)?;
+            require_keys_eq!(escrow.mint_a, self.mint_a.key(), EscrowError::InvalidBatch);
+            require_keys_eq!(escrow.mint_b, self.mint_b.key(), EscrowError::InvalidBatch);
+            require_keys_eq!(escrow.maker, maker.key(), EscrowError::InvalidBatch);
+            require_keys_eq!(
+                vault_info.key(),
+                get_associated_token_address_with_program_id(&escrow.key(), &escrow.mint_a, self.token_program.key),
+                EscrowError::InvalidBatch
+            );
+            require_keys_eq!(
+                maker_ata_b.key(),
+                get_associated_token_address_with_program_id(&escrow.maker, &escrow.mint_b, self.token_program.key),
+                EscrowError::InvalidBatch
+            );
+
+            let expired = escrow.expires_at.is_some_and(|expires_at| now >= expires_at);
+            let receive = escrow.current_receive(now);
+            if now < escrow.unlock_time || expired || receive > remaining {
+                continue;
+            }
+
+            let vault = InterfaceAccount::<TokenAccount>::try_from(vault_info)?;
+            let amount_a = vault.amount;
+
+            self.deposit(maker, maker_ata_b, receive, remaining_accounts)?;
+            self.withdraw_and_close_vault(&escrow, maker, vault_info, amount_a, remaining_accounts)?;
+
+            self.registry.remove(&escrow.key());
+            taken.push(EscrowTaken {
+                escrow: escrow.key(),
+                maker: escrow.maker,
+                taker: self.taker.key(),
+                mint_a: escrow.mint_a,
+                mint_b: escrow.mint_b,
+                seed: escrow.seed,
+                amount_a,
+                amount_b: receive,
+                unlock_time: escrow.unlock_time,
+            });
+            escrow.close(maker.clone())?;
+
+            remaining -= receive;
+        }
+
+        require!(!taken.is_empty(), EscrowError::NothingTaken);
+
+        Ok(taken)
+    }
+
+    fn deposit(
+        &self,
+        maker: &AccountInfo<'info>,
+        maker_ata_b: &AccountInfo<'info>,
+        receive: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let cpi_accounts = Create {
+            payer: self.taker.to_account_info(),
+            associated_token: maker_ata_b.clone(),
+            authority: maker.clone(),
+            mint: self.mint_b.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+
+        create_idempotent(CpiContext::new(self.associated_token_program.to_account_info(), cpi_accounts))?;
+
+        transfer_checked_with_hook(
+            &self.token_program,
+            self.taker_ata_b.to_account_info(),
+            &self.mint_b,
+            maker_ata_b.clone(),
+            self.taker.to_account_info(),
+            remaining_accounts,
+            receive,
+            &[],
+        )
+    }
+
+    fn withdraw_and_close_vault(
+        &self,
+        escrow: &Account<'info, Escrow>,
+        maker: &AccountInfo<'info>,
+        vault: &AccountInfo<'info>,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            maker.key.as_ref(),
+            &escrow.seed.to_le_bytes()[..],
+            &[escrow.bump]
+        ]];
+
+        transfer_checked_with_hook(
+            &self.token_program,
+            vault.clone(),
+            &self.mint_a,
+            self.taker_ata_a.to_account_info(),
+            escrow.to_account_info(),
+            remaining_accounts,
+            amount,
+            &signer_seeds,
+        )?;
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = CloseAccount {
+            account: vault.clone(),
+            destination: maker.clone(),
+            authority: escrow.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
+
+        close_account(cpi_context)
+    }
+}
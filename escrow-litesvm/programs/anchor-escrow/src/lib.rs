@@ -5,9 +5,16 @@ use anchor_lang::prelude::*;
 
 mod state;
 mod instructions;
+mod constants;
+mod error;
+mod utils;
+pub mod metadata;
+pub mod events;
 mod tests;
 
 use instructions::*;
+use events::*;
+use state::PriceCurve;
 
 declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 
@@ -15,17 +22,180 @@ declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 pub mod anchor_escrow {
     use super::*;
 
-    pub fn make(ctx: Context<Make>, seed: u64, deposit: u64, receive: u64) -> Result<()> {
-        ctx.accounts.init_escrow(seed, receive, &ctx.bumps)?;
-        ctx.accounts.deposit(deposit)
+    pub fn make<'info>(
+        ctx: Context<'_, '_, '_, 'info, Make<'info>>,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        lock_seconds: i64,
+        expires_at: Option<i64>,
+        curve: Option<PriceCurve>,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(seed, receive, lock_seconds, expires_at, curve, &ctx.bumps)?;
+        ctx.accounts.register(deposit, &ctx.bumps)?;
+        ctx.accounts.deposit(deposit, ctx.remaining_accounts)?;
+
+        let escrow = &ctx.accounts.escrow;
+        emit_cpi!(EscrowMade {
+            escrow: escrow.key(),
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            seed,
+            deposit,
+            receive,
+            unlock_time: escrow.unlock_time,
+            expires_at: escrow.expires_at,
+            curve: escrow.curve,
+        });
+
+        Ok(())
+    }
+
+    pub fn make_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeNft<'info>>,
+        seed: u64,
+        receive: u64,
+        lock_seconds: i64,
+        expires_at: Option<i64>,
+        collection: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.verify_nft(&collection)?;
+        ctx.accounts.init_escrow(seed, receive, lock_seconds, expires_at, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)?;
+
+        let escrow = &ctx.accounts.escrow;
+        emit_cpi!(EscrowMade {
+            escrow: escrow.key(),
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            seed,
+            deposit: 1,
+            receive,
+            unlock_time: escrow.unlock_time,
+            expires_at: escrow.expires_at,
+            curve: escrow.curve,
+        });
+
+        Ok(())
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+        let amount = ctx.accounts.vault.amount;
+        ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        ctx.accounts.unregister();
+
+        let escrow = &ctx.accounts.escrow;
+        emit_cpi!(EscrowRefunded {
+            escrow: escrow.key(),
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            seed: escrow.seed,
+            amount,
+            unlock_time: escrow.unlock_time,
+        });
+
+        Ok(())
     }
 
-    pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        ctx.accounts.refund_and_close_vault()
+    pub fn take<'info>(
+        ctx: Context<'_, '_, '_, 'info, Take<'info>>,
+        expected_receive: u64,
+        min_give: u64,
+    ) -> Result<()> {
+        let amount_b = ctx.accounts.check_terms(expected_receive, min_give)?;
+
+        let amount_a = ctx.accounts.vault.amount;
+        ctx.accounts.deposit(amount_b, ctx.remaining_accounts)?;
+        ctx.accounts.withdraw_and_close_vault(ctx.remaining_accounts)?;
+        ctx.accounts.unregister();
+
+        let escrow = &ctx.accounts.escrow;
+        emit_cpi!(EscrowTaken {
+            escrow: escrow.key(),
+            maker: escrow.maker,
+            taker: ctx.accounts.taker.key(),
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            seed: escrow.seed,
+            amount_a,
+            amount_b,
+            unlock_time: escrow.unlock_time,
+        });
+
+        Ok(())
     }
 
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        ctx.accounts.deposit()?;
-        ctx.accounts.withdraw_and_close_vault()
+    pub fn take_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TakeMany<'info>>,
+        count: u8,
+        max_spend: u64,
+    ) -> Result<()> {
+        let taken = ctx.accounts.take_many(count, max_spend, ctx.remaining_accounts)?;
+
+        for event in taken {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    pub fn counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, CounterOffer<'info>>,
+        receive: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_counter(receive, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)?;
+
+        let counter = &ctx.accounts.counter;
+        emit_cpi!(CounterOffered {
+            counter: counter.key(),
+            escrow: counter.escrow,
+            taker: counter.taker,
+            mint_b: counter.mint_b,
+            receive,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_counter<'info>(ctx: Context<'_, '_, '_, 'info, AcceptCounter<'info>>) -> Result<()> {
+        let amount_a = ctx.accounts.vault.amount;
+        let amount_b = ctx.accounts.counter_vault.amount;
+        ctx.accounts.settle_counter(ctx.remaining_accounts)?;
+        ctx.accounts.settle_escrow(ctx.remaining_accounts)?;
+
+        let escrow = &ctx.accounts.escrow;
+        emit_cpi!(CounterAccepted {
+            counter: ctx.accounts.counter.key(),
+            escrow: escrow.key(),
+            maker: escrow.maker,
+            taker: ctx.accounts.taker.key(),
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            seed: escrow.seed,
+            amount_a,
+            amount_b,
+        });
+
+        Ok(())
+    }
+
+    pub fn refund_counter<'info>(ctx: Context<'_, '_, '_, 'info, RefundCounter<'info>>) -> Result<()> {
+        let amount = ctx.accounts.counter_vault.amount;
+        ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+
+        let counter = &ctx.accounts.counter;
+        emit_cpi!(CounterRefunded {
+            counter: counter.key(),
+            escrow: counter.escrow,
+            taker: counter.taker,
+            mint_b: counter.mint_b,
+            amount,
+        });
+
+        Ok(())
     }
-}
\ No newline at end of file
+}
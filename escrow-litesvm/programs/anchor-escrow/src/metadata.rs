@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+/// Leading fields of a Metaplex Token Metadata account, up to `collection`.
+///
+/// Only the prefix is decoded, so trailing fields added by newer versions of
+/// the metadata program don't break parsing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct MetadataPrefix {
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<u8>,
+    pub collection: Option<Collection>,
+}
+
+impl MetadataPrefix {
+    /// `Key::MetadataV1` in the metadata program
+    pub const METADATA_V1: u8 = 4;
+
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let metadata = Self::deserialize(&mut &data[..]).ok()?;
+        (metadata.key == Self::METADATA_V1).then_some(metadata)
+    }
+
+    /// Verified member of `collection`
+    pub fn in_collection(&self, collection: &Pubkey) -> bool {
+        self.collection
+            .as_ref()
+            .is_some_and(|c| c.verified && c.key == *collection)
+    }
+}
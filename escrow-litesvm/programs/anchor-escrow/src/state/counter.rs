@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+/// A taker's counter-offer on an `Escrow`: `receive` of mint B sits in the
+/// counter vault until the maker accepts or the taker takes it back.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Counter {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: u8,
+}
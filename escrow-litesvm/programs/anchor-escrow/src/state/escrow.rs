@@ -1,5 +1,49 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::MAX_LOCK_SECONDS;
+use crate::error::EscrowError;
+
+/// Dutch-auction pricing: the ask falls linearly from `start_receive` at
+/// `start_time` to `end_receive` at `end_time` and stays there.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
+pub struct PriceCurve {
+    pub start_receive: u64,
+    pub end_receive: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl PriceCurve {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.start_receive > self.end_receive
+                && self.end_receive > 0
+                && self.end_time > self.start_time,
+            EscrowError::InvalidPriceCurve
+        );
+
+        Ok(())
+    }
+
+    /// Price at `now`. The decay is rounded down, so the price rounds up in
+    /// the maker's favour and never drops below `end_receive`.
+    pub fn price_at(&self, now: i64) -> u64 {
+        if now <= self.start_time {
+            return self.start_receive;
+        }
+        if now >= self.end_time {
+            return self.end_receive;
+        }
+
+        let elapsed = (now - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let range = (self.start_receive - self.end_receive) as u128;
+
+        // elapsed < duration, so the decay is strictly less than the range
+        self.start_receive - (range * elapsed / duration) as u64
+    }
+}
+
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct Escrow {
@@ -9,5 +53,32 @@ pub struct Escrow {
     pub mint_b: Pubkey,
     pub receive: u64,
     pub unlock_time: i64,
+    pub expires_at: Option<i64>,
+    pub curve: Option<PriceCurve>,
     pub bump: u8,
-}
\ No newline at end of file
+}
+
+impl Escrow {
+    /// Validates a maker's lock and expiry, returning the unlock time
+    pub fn unlock_time_from(now: i64, lock_seconds: i64, expires_at: Option<i64>) -> Result<i64> {
+        require!(
+            (0..=MAX_LOCK_SECONDS).contains(&lock_seconds),
+            EscrowError::InvalidLockPeriod
+        );
+
+        let unlock_time = now
+            .checked_add(lock_seconds)
+            .ok_or(EscrowError::InvalidLockPeriod)?;
+
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > unlock_time, EscrowError::InvalidExpiry);
+        }
+
+        Ok(unlock_time)
+    }
+
+    /// What the taker pays in mint B at `now`
+    pub fn current_receive(&self, now: i64) -> u64 {
+        self.curve.map_or(self.receive, |curve| curve.price_at(now))
+    }
+}
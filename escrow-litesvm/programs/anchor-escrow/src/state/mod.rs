@@ -1,3 +1,7 @@
+pub mod counter;
 pub mod escrow;
+pub mod pair_registry;
 
-pub use escrow::*;
\ No newline at end of file
+pub use counter::*;
+pub use escrow::*;
+pub use pair_registry::*;
\ No newline at end of file
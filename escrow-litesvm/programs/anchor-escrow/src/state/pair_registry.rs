@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_PAIR_ENTRIES;
+use crate::error::EscrowError;
+use crate::state::{Escrow, PriceCurve};
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq)]
+pub struct PairEntry {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    /// Mint A deposited into the vault
+    pub deposit: u64,
+    /// Mint B asked in return, the starting price for a price curve
+    pub receive: u64,
+    pub unlock_time: i64,
+    pub expires_at: Option<i64>,
+    pub curve: Option<PriceCurve>,
+}
+
+impl PairEntry {
+    pub fn new(escrow_key: Pubkey, escrow: &Escrow, deposit: u64) -> Self {
+        Self {
+            escrow: escrow_key,
+            maker: escrow.maker,
+            deposit,
+            receive: escrow.receive,
+            unlock_time: escrow.unlock_time,
+            expires_at: escrow.expires_at,
+            curve: escrow.curve,
+        }
+    }
+
+    /// What a taker would pay in mint B at `now`, same as `Escrow::current_receive`
+    pub fn current_receive(&self, now: i64) -> u64 {
+        self.curve.map_or(self.receive, |curve| curve.price_at(now))
+    }
+
+    /// Whether the escrow can be taken at `now`
+    pub fn is_open(&self, now: i64) -> bool {
+        now >= self.unlock_time && !self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+/// Open offers for a (mint_a, mint_b) pair, so takers can find every escrow
+/// for the pair with a single account fetch.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct PairRegistry {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    #[max_len(MAX_PAIR_ENTRIES)]
+    pub entries: Vec<PairEntry>,
+    pub bump: u8,
+}
+
+impl PairRegistry {
+    /// Lists a new escrow for (`mint_a`, `mint_b`), used by every instruction
+    /// that makes one
+    pub fn register(&mut self, mint_a: Pubkey, mint_b: Pubkey, bump: u8, entry: PairEntry) -> Result<()> {
+        // First offer for this pair creates the registry
+        if self.mint_a == Pubkey::default() {
+            self.mint_a = mint_a;
+            self.mint_b = mint_b;
+            self.bump = bump;
+        }
+
+        self.push(entry)
+    }
+
+    /// Lists `entry`, failing when the registry is full rather than dropping
+    /// a live offer takers could no longer find
+    pub fn push(&mut self, entry: PairEntry) -> Result<()> {
+        require!(self.entries.len() < MAX_PAIR_ENTRIES, EscrowError::RegistryFull);
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Drops the entry for `escrow`, if it's listed
+    pub fn remove(&mut self, escrow: &Pubkey) {
+        self.entries.retain(|entry| entry.escrow != *escrow);
+    }
+}
@@ -0,0 +1,160 @@
+//! Account fixtures for LiteSVM tests.
+//!
+//! Fixtures use the same JSON layout as `solana account <ADDRESS> --output json`,
+//! so a dump captured from a live cluster can be dropped into a fixtures
+//! directory and loaded without network access:
+//!
+//! ```json
+//! {
+//!   "pubkey": "...",
+//!   "account": {
+//!     "lamports": 1461600,
+//!     "data": ["<base64>", "base64"],
+//!     "owner": "...",
+//!     "executable": false,
+//!     "rentEpoch": 0,
+//!     "space": 82
+//!   }
+//! }
+//! ```
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    litesvm::LiteSVM,
+    serde::{Deserialize, Serialize},
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAccountFixture {
+    pub lamports: u64,
+    /// `[data, encoding]`, only `base64` is supported
+    pub data: (String, String),
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub space: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AccountFixture {
+    pub pubkey: String,
+    pub account: UiAccountFixture,
+}
+
+impl AccountFixture {
+    pub fn new(pubkey: &Pubkey, account: &Account) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            account: UiAccountFixture {
+                lamports: account.lamports,
+                data: (STANDARD.encode(&account.data), "base64".to_string()),
+                owner: account.owner.to_string(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+                space: account.data.len() as u64,
+            },
+        }
+    }
+
+    pub fn address(&self) -> Pubkey {
+        Pubkey::from_str(&self.pubkey).expect("Invalid fixture pubkey")
+    }
+
+    pub fn to_account(&self) -> Account {
+        let (data, encoding) = &self.account.data;
+        assert_eq!(encoding, "base64", "Unsupported fixture encoding: {}", encoding);
+
+        Account {
+            lamports: self.account.lamports,
+            data: STANDARD.decode(data).expect("Invalid base64 fixture data"),
+            owner: Pubkey::from_str(&self.account.owner).expect("Invalid fixture owner"),
+            executable: self.account.executable,
+            rent_epoch: self.account.rent_epoch,
+        }
+    }
+
+    pub fn read(path: &Path) -> Self {
+        let json = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e));
+        serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("Failed to parse fixture {}: {}", path.display(), e))
+    }
+
+    pub fn write(&self, path: &Path) {
+        let json = serde_json::to_string_pretty(self).expect("Failed to serialize fixture");
+        fs::write(path, json)
+            .unwrap_or_else(|e| panic!("Failed to write fixture {}: {}", path.display(), e));
+    }
+}
+
+/// Directory holding the checked-in fixtures, `<crate>/fixtures`
+pub fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// Writes `address` as `<dir>/<address>.json`. Returns `false` if the account
+/// doesn't exist in the SVM.
+pub fn save_account(svm: &LiteSVM, address: &Pubkey, dir: &Path) -> bool {
+    let Some(account) = svm.get_account(address) else {
+        return false;
+    };
+
+    fs::create_dir_all(dir).expect("Failed to create fixtures directory");
+    AccountFixture::new(address, &account).write(&dir.join(format!("{}.json", address)));
+    true
+}
+
+/// Loads a single fixture file into the SVM at the address recorded in it.
+pub fn load_account(svm: &mut LiteSVM, path: &Path) -> Pubkey {
+    let fixture = AccountFixture::read(path);
+    let address = fixture.address();
+
+    svm.set_account(address, fixture.to_account())
+        .unwrap_or_else(|e| panic!("Failed to set fixture account {}: {:?}", address, e));
+    address
+}
+
+/// Loads every `*.json` fixture in `dir`, returning the addresses in file name order.
+pub fn load_dir(svm: &mut LiteSVM, dir: &Path) -> Vec<Pubkey> {
+    let mut paths = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Failed to read fixtures directory {}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths.iter().map(|path| load_account(svm, path)).collect()
+}
+
+/// Snapshots the SVM after a test step.
+///
+/// LiteSVM doesn't expose its accounts db for iteration, so the caller lists
+/// every address the test touched. Accounts that were closed (missing or with
+/// no lamports) are skipped and any stale fixture for them is removed, so
+/// loading the snapshot reproduces the state as it was.
+pub fn snapshot(svm: &LiteSVM, addresses: &[Pubkey], dir: &Path) -> Vec<Pubkey> {
+    fs::create_dir_all(dir).expect("Failed to create snapshot directory");
+
+    let mut saved = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        let path = dir.join(format!("{}.json", address));
+        match svm.get_account(address) {
+            Some(account) if account.lamports > 0 => {
+                AccountFixture::new(address, &account).write(&path);
+                saved.push(*address);
+            }
+            _ => {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+    saved
+}
@@ -0,0 +1,341 @@
+#[cfg(test)]
+mod tests {
+    use {
+        anchor_lang::{
+            prelude::*,
+            InstructionData,
+            ToAccountMetas,
+        },
+        anchor_spl::{
+            associated_token::{self, spl_associated_token_account},
+            token_2022::spl_token_2022::{
+                self,
+                extension::{ExtensionType, StateWithExtensions, transfer_hook},
+                state::Mint as MintState,
+                instruction,
+            },
+        },
+        litesvm::LiteSVM,
+        solana_instruction::Instruction,
+        solana_keypair::Keypair,
+        solana_native_token::LAMPORTS_PER_SOL,
+        solana_pubkey::Pubkey,
+        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+        solana_signer::Signer,
+        solana_transaction::Transaction,
+        std::path::PathBuf,
+    };
+
+    static PROGRAM_ID: Pubkey = crate::ID;
+    static HOOK_PROGRAM_ID: Pubkey = whitelist_transfer_hook::ID;
+
+    fn pair_registry(mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"pair", mint_a.as_ref(), mint_b.as_ref()], &PROGRAM_ID).0
+    }
+
+    /// Sets up LiteSVM and loads both the escrow and the whitelist hook programs
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new();
+
+        svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL");
+
+        let escrow_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy/anchor_escrow.so");
+        let escrow_program_data = std::fs::read(escrow_so_path)
+            .expect("Failed to read escrow program");
+        svm.add_program(PROGRAM_ID, &escrow_program_data);
+
+        let hook_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../whitelist-transfer-hook/target/deploy/whitelist_transfer_hook.so");
+        let hook_program_data = std::fs::read(hook_so_path)
+            .expect("Failed to read hook program");
+        svm.add_program(HOOK_PROGRAM_ID, &hook_program_data);
+
+        (svm, payer)
+    }
+
+    fn send(svm: &mut LiteSVM, ixs: &[Instruction], payer: &Keypair, signers: &[&Keypair]) -> std::result::Result<(), String> {
+        let blockhash = svm.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&payer.pubkey()), signers, blockhash);
+        svm.send_transaction(tx)
+            .map(|_| ())
+            .map_err(|e| format!("{:?}", e.err))
+    }
+
+    /// Creates a Token-2022 mint, with the whitelist hook attached if `hooked`
+    fn create_mint(svm: &mut LiteSVM, payer: &Keypair, hooked: bool) -> Pubkey {
+        let mint_keypair = Keypair::new();
+
+        let extensions = if hooked { vec![ExtensionType::TransferHook] } else { vec![] };
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions)
+            .expect("Failed to calculate mint space");
+        let rent = svm.minimum_balance_for_rent_exemption(space);
+
+        let mut ixs = vec![solana_system_interface::instruction::create_account(
+            &payer.pubkey(),
+            &mint_keypair.pubkey(),
+            rent,
+            space as u64,
+            &spl_token_2022::ID,
+        )];
+
+        if hooked {
+            ixs.push(transfer_hook::instruction::initialize(
+                &spl_token_2022::ID,
+                &mint_keypair.pubkey(),
+                Some(payer.pubkey()),
+                Some(HOOK_PROGRAM_ID),
+            ).expect("Failed to create initialize transfer hook instruction"));
+        }
+
+        ixs.push(instruction::initialize_mint(
+            &spl_token_2022::ID,
+            &mint_keypair.pubkey(),
+            &payer.pubkey(),
+            None,
+            6,
+        ).expect("Failed to create initialize mint instruction"));
+
+        send(svm, &ixs, payer, &[payer, &mint_keypair]).expect("Mint creation failed");
+        mint_keypair.pubkey()
+    }
+
+    fn extra_account_meta_list(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    fn whitelist_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), user.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    fn initialize_extra_account_meta_list(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey) {
+        let ix = Instruction {
+            program_id: HOOK_PROGRAM_ID,
+            accounts: whitelist_transfer_hook::accounts::InitializeExtraAccountMetaList {
+                payer: payer.pubkey(),
+                extra_account_meta_list: extra_account_meta_list(mint),
+                mint: *mint,
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: whitelist_transfer_hook::instruction::InitializeTransferHook {}.data(),
+        };
+
+        send(svm, &[ix], payer, &[payer]).expect("ExtraAccountMetaList init failed");
+    }
+
+    fn whitelist_user(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, user: &Pubkey) {
+        let ix = Instruction {
+            program_id: HOOK_PROGRAM_ID,
+            accounts: whitelist_transfer_hook::accounts::InitializeWhitelist {
+                admin: payer.pubkey(),
+                user: *user,
+                mint: *mint,
+                whitelist: whitelist_pda(mint, user),
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: whitelist_transfer_hook::instruction::InitializeWhitelist {}.data(),
+        };
+
+        send(svm, &[ix], payer, &[payer]).expect("Whitelist failed");
+    }
+
+    /// Creates `owner`'s Token-2022 ATA and mints `amount` into it
+    fn mint_tokens(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let ata = associated_token::get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::ID);
+
+        let create_ata_ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            owner,
+            mint,
+            &spl_token_2022::ID,
+        );
+
+        let mint_to_ix = instruction::mint_to(
+            &spl_token_2022::ID,
+            mint,
+            &ata,
+            &payer.pubkey(),
+            &[],
+            amount,
+        ).expect("Failed to create mint_to instruction");
+
+        send(svm, &[create_ata_ix, mint_to_ix], payer, &[payer]).expect("Mint to user failed");
+        ata
+    }
+
+    fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = svm.get_account(token_account).expect("Token account not found");
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("Failed to unpack token account")
+            .base
+            .amount
+    }
+
+    /// Extra accounts the whitelist hook resolves for a transfer from
+    /// `sender` to a token account owned by `recipient`
+    fn hook_accounts(mint: &Pubkey, sender: &Pubkey, recipient: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(extra_account_meta_list(mint), false),
+            AccountMeta::new_readonly(whitelist_pda(mint, sender), false),
+            AccountMeta::new_readonly(*recipient, false),
+            AccountMeta::new_readonly(whitelist_pda(mint, recipient), false),
+        ]
+    }
+
+    struct HookedEscrow {
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        maker_ata_a: Pubkey,
+        escrow: Pubkey,
+        vault: Pubkey,
+    }
+
+    /// Escrows 10 of a hooked mint A for 10 of a plain Token-2022 mint B
+    fn make_hooked_escrow(svm: &mut LiteSVM, maker: &Keypair, seed: u64) -> HookedEscrow {
+        let mint_a = create_mint(svm, maker, true);
+        let mint_b = create_mint(svm, maker, false);
+        initialize_extra_account_meta_list(svm, maker, &mint_a);
+
+        let escrow = Pubkey::find_program_address(
+            &[b"escrow", maker.pubkey().as_ref(), &seed.to_le_bytes()],
+            &PROGRAM_ID,
+        ).0;
+        let vault = associated_token::get_associated_token_address_with_program_id(&escrow, &mint_a, &spl_token_2022::ID);
+
+        whitelist_user(svm, maker, &mint_a, &maker.pubkey());
+        whitelist_user(svm, maker, &mint_a, &escrow);
+
+        let maker_ata_a = mint_tokens(svm, maker, &mint_a, &maker.pubkey(), 1_000);
+
+        let mut accounts = crate::accounts::Make {
+            maker: maker.pubkey(),
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            escrow,
+            registry: pair_registry(&mint_a, &mint_b),
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: spl_token_2022::ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0,
+            program: PROGRAM_ID,
+        }.to_account_metas(None);
+        accounts.extend(hook_accounts(&mint_a, &maker.pubkey(), &escrow));
+
+        let make_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Make {
+                seed,
+                deposit: 10,
+                receive: 10,
+                lock_seconds: 0,
+                expires_at: None,
+                curve: None,
+            }.data(),
+        };
+
+        send(svm, &[make_ix], maker, &[maker]).expect("Make with hooked mint failed");
+        assert_eq!(token_balance(svm, &vault), 10);
+
+        HookedEscrow { mint_a, mint_b, maker_ata_a, escrow, vault }
+    }
+
+    fn take_ix(maker: &Pubkey, taker: &Pubkey, escrow: &HookedEscrow) -> Instruction {
+        let mut accounts = crate::accounts::Take {
+            taker: *taker,
+            maker: *maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            taker_ata_a: associated_token::get_associated_token_address_with_program_id(taker, &escrow.mint_a, &spl_token_2022::ID),
+            taker_ata_b: associated_token::get_associated_token_address_with_program_id(taker, &escrow.mint_b, &spl_token_2022::ID),
+            maker_ata_b: associated_token::get_associated_token_address_with_program_id(maker, &escrow.mint_b, &spl_token_2022::ID),
+            escrow: escrow.escrow,
+            registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+            vault: escrow.vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: spl_token_2022::ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0,
+            program: PROGRAM_ID,
+        }.to_account_metas(None);
+        // Only mint A is hooked, mint B is transferred without extra accounts
+        accounts.extend(hook_accounts(&escrow.mint_a, &escrow.escrow, taker));
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Take { expected_receive: 10, min_give: 10 }.data(),
+        }
+    }
+
+    #[test]
+    fn test_take_hooked_mint_with_whitelisted_parties() {
+        let (mut svm, maker) = setup();
+        let escrow = make_hooked_escrow(&mut svm, &maker, 1);
+
+        let taker = Keypair::new();
+        svm.airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        mint_tokens(&mut svm, &maker, &escrow.mint_b, &taker.pubkey(), 1_000);
+
+        // Not whitelisted yet, so the hook rejects the vault -> taker transfer
+        let result = send(&mut svm, &[take_ix(&maker.pubkey(), &taker.pubkey(), &escrow)], &taker, &[&taker]);
+        msg!("Take by non-whitelisted taker: {:?}", result);
+        assert!(result.is_err(), "Hook should reject a non-whitelisted taker");
+        assert_eq!(token_balance(&svm, &escrow.vault), 10);
+
+        whitelist_user(&mut svm, &maker, &escrow.mint_a, &taker.pubkey());
+        svm.expire_blockhash();
+
+        send(&mut svm, &[take_ix(&maker.pubkey(), &taker.pubkey(), &escrow)], &taker, &[&taker])
+            .expect("Take with whitelisted taker failed");
+
+        let taker_ata_a = associated_token::get_associated_token_address_with_program_id(
+            &taker.pubkey(),
+            &escrow.mint_a,
+            &spl_token_2022::ID,
+        );
+        let maker_ata_b = associated_token::get_associated_token_address_with_program_id(
+            &maker.pubkey(),
+            &escrow.mint_b,
+            &spl_token_2022::ID,
+        );
+        assert_eq!(token_balance(&svm, &taker_ata_a), 10);
+        assert_eq!(token_balance(&svm, &maker_ata_b), 10);
+        assert!(svm.get_account(&escrow.vault).map_or(true, |a| a.data.is_empty()));
+    }
+
+    #[test]
+    fn test_refund_hooked_mint() {
+        let (mut svm, maker) = setup();
+        let escrow = make_hooked_escrow(&mut svm, &maker, 1);
+        assert_eq!(token_balance(&svm, &escrow.maker_ata_a), 990);
+
+        let mut accounts = crate::accounts::Refund {
+            maker: maker.pubkey(),
+            mint_a: escrow.mint_a,
+            maker_ata_a: escrow.maker_ata_a,
+            escrow: escrow.escrow,
+            registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+            vault: escrow.vault,
+            token_program: spl_token_2022::ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0,
+            program: PROGRAM_ID,
+        }.to_account_metas(None);
+        accounts.extend(hook_accounts(&escrow.mint_a, &escrow.escrow, &maker.pubkey()));
+
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::Refund {}.data(),
+        };
+
+        send(&mut svm, &[refund_ix], &maker, &[&maker]).expect("Refund with hooked mint failed");
+        assert_eq!(token_balance(&svm, &escrow.maker_ata_a), 1_000);
+    }
+}
@@ -1,3 +1,8 @@
+mod hook;
+
+#[cfg(test)]
+mod fixtures;
+
 #[cfg(test)]
 mod tests {
     use anchor_lang::{solana_program, AnchorSerialize};
@@ -6,6 +11,7 @@ mod tests {
             prelude::msg,
             solana_program::program_pack::Pack,
             AccountDeserialize,
+            AccountSerialize,
             InstructionData,
             ToAccountMetas
         }, anchor_spl::{
@@ -15,15 +21,14 @@ mod tests {
             },
             token::spl_token
         },
-        litesvm::LiteSVM,
+        litesvm::{types::TransactionMetadata, LiteSVM},
         litesvm_token::{
             spl_token::ID as TOKEN_PROGRAM_ID,
             CreateAssociatedTokenAccount,
             CreateMint, MintTo
         },
-        solana_rpc_client::rpc_client::RpcClient,
         solana_account::Account,
-        solana_instruction::Instruction,
+        solana_instruction::{AccountMeta, Instruction},
         solana_keypair::Keypair,
         solana_message::Message,
         solana_native_token::LAMPORTS_PER_SOL,
@@ -31,16 +36,33 @@ mod tests {
         solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
         solana_signer::Signer,
         solana_transaction::Transaction,
-        solana_address::Address,
-        std::{
-            path::PathBuf,
-            str::FromStr
-        }
+        std::path::PathBuf
     };
-    use crate::state::Escrow;
+    use crate::constants::TOKEN_METADATA_PROGRAM_ID;
+    use crate::events::EscrowView;
+    use crate::metadata::{Collection, MetadataPrefix};
+    use crate::state::{Escrow, PairEntry, PairRegistry, PriceCurve};
+    use super::fixtures;
 
     static PROGRAM_ID: Pubkey = crate::ID;
 
+    fn pair_registry(mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"pair", mint_a.as_ref(), mint_b.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn event_authority() -> Pubkey {
+        Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0
+    }
+
+    // Events emitted through `emit_cpi!` show up as inner instructions
+    fn escrow_events(meta: &TransactionMetadata) -> Vec<EscrowView> {
+        meta.inner_instructions
+            .iter()
+            .flatten()
+            .filter_map(|inner| EscrowView::decode(&inner.instruction.data))
+            .collect()
+    }
+
     fn setup() -> (LiteSVM, Keypair) {
         // Initialize LiteSVM and payer
         let mut program = LiteSVM::new();
@@ -59,22 +81,8 @@ mod tests {
 
         program.add_program(PROGRAM_ID, &program_data);
 
-        // Example on how to Load an account from devnet
-        // let rpc_client = RpcClient::new("https://api.devnet.solana.com");
-        // let account_address = Address::from_str("DRYvf71cbF2s5wgaJQvAGkghMkRcp5arvsK2w97vXhi2").unwrap();
-        // let fetched_account = rpc_client
-        //     .get_account(&account_address)
-        //     .expect("Failed to fetch account from devnet");
-        //
-        // program.set_account(payer.pubkey(), Account {
-        //     lamports: fetched_account.lamports,
-        //     data: fetched_account.data,
-        //     owner: Pubkey::from(fetched_account.owner.to_bytes()),
-        //     executable: fetched_account.executable,
-        //     rent_epoch: fetched_account.rent_epoch
-        // }).unwrap();
-
-        // msg!("Lamports of fetched account: {}", fetched_account.lamports);
+        // Accounts captured from a cluster can be loaded offline with
+        // `fixtures::load_dir(&mut program, &fixtures::fixtures_dir())`
 
         // Return the LiteSVM instance and payer keypair
         (program, payer)
@@ -140,12 +148,15 @@ mod tests {
                 mint_b: mint_b,
                 maker_ata_a: maker_ata_a,
                 escrow: escrow,
+                registry: pair_registry(&mint_a, &mint_b),
                 vault: vault,
                 associated_token_program: asspciated_token_program,
                 token_program: token_program,
                 system_program: system_program,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
             }.to_account_metas(None),
-            data: crate::instruction::Make {deposit: 10, seed: 123u64, receive: 10 }.data(),
+            data: crate::instruction::Make {deposit: 10, seed: 123u64, receive: 10, lock_seconds: 1800, expires_at: None, curve: None }.data(),
         };
 
         // Create and send the transaction containing the "Make" instruction
@@ -177,6 +188,19 @@ mod tests {
         assert_eq!(escrow_data.mint_b, mint_b);
         assert_eq!(escrow_data.receive, 10);
 
+        let events = escrow_events(&tx);
+        assert_eq!(events.len(), 1);
+        let EscrowView::Made(made) = &events[0] else {
+            panic!("Expected EscrowMade, got {:?}", events[0]);
+        };
+        assert_eq!(made.escrow, escrow);
+        assert_eq!(made.maker, maker);
+        assert_eq!(made.mint_a, mint_a);
+        assert_eq!(made.mint_b, mint_b);
+        assert_eq!(made.seed, 123u64);
+        assert_eq!(made.deposit, 10);
+        assert_eq!(made.receive, 10);
+        assert_eq!(made.unlock_time, escrow_data.unlock_time);
     }
     // #[test]
     // fn test_take(){
@@ -404,15 +428,21 @@ mod tests {
                 mint_b,
                 maker_ata_a,
                 escrow,
+                registry: pair_registry(&mint_a, &mint_b),
                 vault,
                 associated_token_program: spl_associated_token_account::ID,
                 token_program: TOKEN_PROGRAM_ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
             }.to_account_metas(None),
             data: crate::instruction::Make {
                 deposit: 10,
                 seed,
-                receive: 10
+                receive: 10,
+                lock_seconds: 1800,
+                expires_at: None,
+                curve: None,
             }.data(),
         };
 
@@ -474,12 +504,15 @@ mod tests {
                 taker_ata_b,
                 maker_ata_b,
                 escrow,
+                registry: pair_registry(&mint_a, &mint_b),
                 vault,
                 associated_token_program: spl_associated_token_account::ID,
                 token_program: TOKEN_PROGRAM_ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
             }.to_account_metas(None),
-            data: crate::instruction::Take {}.data(),
+            data: crate::instruction::Take { expected_receive: 10, min_give: 10 }.data(),
         };
 
         let message = Message::new(&[take_ix_early], Some(&taker.pubkey()));
@@ -518,12 +551,15 @@ mod tests {
                 taker_ata_b,
                 maker_ata_b,
                 escrow,
+                registry: pair_registry(&mint_a, &mint_b),
                 vault,
                 associated_token_program: spl_associated_token_account::ID,
                 token_program: TOKEN_PROGRAM_ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
             }.to_account_metas(None),
-            data: crate::instruction::Take {}.data(),
+            data: crate::instruction::Take { expected_receive: 10, min_give: 10 }.data(),
         };
 
         let message = Message::new(&[take_ix_exact], Some(&taker.pubkey()));
@@ -577,12 +613,15 @@ mod tests {
                 taker_ata_b,
                 maker_ata_b,
                 escrow,
+                registry: pair_registry(&mint_a, &mint_b),
                 vault,
                 associated_token_program: spl_associated_token_account::ID,
                 token_program: TOKEN_PROGRAM_ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
             }.to_account_metas(None),
-            data: crate::instruction::Take {}.data(),
+            data: crate::instruction::Take { expected_receive: 10, min_give: 10 }.data(),
         };
 
         let message = Message::new(&[take_ix_valid], Some(&taker.pubkey()));
@@ -606,4 +645,1257 @@ mod tests {
         msg!("All assertions passed! Escrow completed successfully after 30-minute unlock period.");
     }
 
-}
\ No newline at end of file
+    fn set_clock(program: &mut LiteSVM, unix_timestamp: i64) {
+        let mut clock = program.get_sysvar::<solana_program::clock::Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        program.set_sysvar::<solana_program::clock::Clock>(&clock);
+        program.expire_blockhash();
+    }
+
+    #[derive(Clone)]
+    struct TestEscrow {
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        maker_ata_a: Pubkey,
+        escrow: Pubkey,
+        vault: Pubkey,
+    }
+
+    // Creates both mints, funds the maker and sends a Make instruction
+    fn make_escrow(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        seed: u64,
+        lock_seconds: i64,
+        expires_at: Option<i64>,
+    ) -> (TestEscrow, Result<TransactionMetadata, String>) {
+        let maker = payer.pubkey();
+
+        let mint_a = CreateMint::new(program, payer).decimals(6).authority(&maker).send().unwrap();
+        let mint_b = CreateMint::new(program, payer).decimals(6).authority(&maker).send().unwrap();
+
+        make_offer(program, payer, payer, &mint_a, &mint_b, crate::instruction::Make {
+            deposit: 10,
+            seed,
+            receive: 10,
+            lock_seconds,
+            expires_at,
+            curve: None,
+        })
+    }
+
+    // Funds `maker` with Mint A (minted by `mint_authority`) and sends a Make instruction
+    fn make_offer(
+        program: &mut LiteSVM,
+        mint_authority: &Keypair,
+        maker: &Keypair,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        args: crate::instruction::Make,
+    ) -> (TestEscrow, Result<TransactionMetadata, String>) {
+        let (mint_a, mint_b) = (*mint_a, *mint_b);
+
+        let maker_ata_a = CreateAssociatedTokenAccount::new(program, maker, &mint_a)
+            .owner(&maker.pubkey()).send().unwrap();
+        MintTo::new(program, mint_authority, &mint_a, &maker_ata_a, 1_000).send().unwrap();
+
+        let escrow = Pubkey::find_program_address(
+            &[b"escrow", maker.pubkey().as_ref(), &args.seed.to_le_bytes()],
+            &PROGRAM_ID,
+        ).0;
+        let vault = associated_token::get_associated_token_address(&escrow, &mint_a);
+
+        let make_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Make {
+                maker: maker.pubkey(),
+                mint_a,
+                mint_b,
+                maker_ata_a,
+                escrow,
+                registry: pair_registry(&mint_a, &mint_b),
+                vault,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: args.data(),
+        };
+
+        let message = Message::new(&[make_ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[maker], message, program.latest_blockhash());
+        let result = program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err));
+
+        (TestEscrow { maker: maker.pubkey(), mint_a, mint_b, maker_ata_a, escrow, vault }, result)
+    }
+
+    // Funds a taker with Mint B (minted by `payer`) and sends a Take instruction against `escrow`
+    fn take_escrow(program: &mut LiteSVM, payer: &Keypair, escrow: &TestEscrow) -> Result<TransactionMetadata, String> {
+        take_escrow_with_terms(program, payer, escrow, 10, 10)
+    }
+
+    fn take_escrow_with_terms(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        escrow: &TestEscrow,
+        expected_receive: u64,
+        min_give: u64,
+    ) -> Result<TransactionMetadata, String> {
+        let maker = escrow.maker;
+        let taker = Keypair::new();
+        program.airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &escrow.mint_a);
+        let taker_ata_b = CreateAssociatedTokenAccount::new(program, &taker, &escrow.mint_b)
+            .owner(&taker.pubkey()).send().unwrap();
+        MintTo::new(program, payer, &escrow.mint_b, &taker_ata_b, 1_000).send().unwrap();
+        let maker_ata_b = associated_token::get_associated_token_address(&maker, &escrow.mint_b);
+
+        let take_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Take {
+                taker: taker.pubkey(),
+                maker,
+                mint_a: escrow.mint_a,
+                mint_b: escrow.mint_b,
+                taker_ata_a,
+                taker_ata_b,
+                maker_ata_b,
+                escrow: escrow.escrow,
+                registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+                vault: escrow.vault,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::Take { expected_receive, min_give }.data(),
+        };
+
+        let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+        let tx = Transaction::new(&[&taker], message, program.latest_blockhash());
+        program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err))
+    }
+
+    fn refund_escrow(program: &mut LiteSVM, payer: &Keypair, escrow: &TestEscrow) -> Result<TransactionMetadata, String> {
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Refund {
+                maker: payer.pubkey(),
+                mint_a: escrow.mint_a,
+                maker_ata_a: escrow.maker_ata_a,
+                escrow: escrow.escrow,
+                registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+                vault: escrow.vault,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        };
+
+        let message = Message::new(&[refund_ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[payer], message, program.latest_blockhash());
+        program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err))
+    }
+
+    #[test]
+    fn test_take_with_zero_lock() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 0, None);
+        let made = escrow_events(&result.unwrap());
+        assert!(matches!(&made[..], [EscrowView::Made(e)] if e.unlock_time == 1_000_000));
+
+        let escrow_account = program.get_account(&escrow.escrow).unwrap();
+        let escrow_data = Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+        assert_eq!(escrow_data.unlock_time, 1_000_000);
+        assert_eq!(escrow_data.expires_at, None);
+
+        // No lock, so the offer can be taken in the same second it was made
+        let meta = take_escrow(&mut program, &payer, &escrow).unwrap();
+        let events = escrow_events(&meta);
+        assert_eq!(events.len(), 1);
+        let EscrowView::Taken(taken) = &events[0] else {
+            panic!("Expected EscrowTaken, got {:?}", events[0]);
+        };
+        assert_eq!(taken.escrow, escrow.escrow);
+        assert_eq!(taken.maker, payer.pubkey());
+        assert_ne!(taken.taker, payer.pubkey());
+        assert_eq!(taken.amount_a, 10);
+        assert_eq!(taken.amount_b, 10);
+        assert!(program.get_account(&escrow.escrow).map_or(true, |a| a.data.is_empty()));
+    }
+
+    #[test]
+    fn test_make_rejects_invalid_lock() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (_, result) = make_escrow(&mut program, &payer, 1, crate::constants::MAX_LOCK_SECONDS + 1, None);
+        assert!(result.is_err(), "Lock above the maximum should be rejected");
+
+        let (_, result) = make_escrow(&mut program, &payer, 2, -1, None);
+        assert!(result.is_err(), "Negative lock should be rejected");
+
+        // Expiry has to leave a window after the unlock time
+        let (_, result) = make_escrow(&mut program, &payer, 3, 1800, Some(1_000_000 + 1800));
+        assert!(result.is_err(), "Expiry at the unlock time should be rejected");
+    }
+
+    #[test]
+    fn test_take_after_expiry_fails() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 1800, Some(1_000_000 + 3600));
+        result.unwrap();
+
+        set_clock(&mut program, 1_000_000 + 3600);
+        let result = take_escrow(&mut program, &payer, &escrow);
+        msg!("Take after expiry result: {:?}", result);
+        assert!(result.is_err(), "Expected error: escrow has expired");
+
+        // The maker can still get their tokens back
+        refund_escrow(&mut program, &payer, &escrow).unwrap();
+        let maker_ata_a_account = program.get_account(&escrow.maker_ata_a).unwrap();
+        let maker_ata_a_data = spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
+        assert_eq!(maker_ata_a_data.amount, 1_000);
+    }
+
+    #[test]
+    fn test_refund_respects_lock() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 1800, None);
+        result.unwrap();
+
+        set_clock(&mut program, 1_000_000 + 900);
+        let result = refund_escrow(&mut program, &payer, &escrow);
+        assert!(result.is_err(), "Expected error: cannot refund before unlock time");
+
+        set_clock(&mut program, 1_000_000 + 1800);
+        let meta = refund_escrow(&mut program, &payer, &escrow).unwrap();
+        assert_eq!(
+            escrow_events(&meta),
+            vec![EscrowView::Refunded(crate::events::EscrowRefunded {
+                escrow: escrow.escrow,
+                maker: payer.pubkey(),
+                mint_a: escrow.mint_a,
+                mint_b: escrow.mint_b,
+                seed: 1,
+                amount: 10,
+                unlock_time: 1_000_000 + 1800,
+            })]
+        );
+
+        let maker_ata_a_account = program.get_account(&escrow.maker_ata_a).unwrap();
+        let maker_ata_a_data = spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
+        assert_eq!(maker_ata_a_data.amount, 1_000);
+    }
+
+    #[test]
+    fn test_snapshot_and_restore_escrow() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 1800, None);
+        result.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("anchor-escrow-snapshot-{}", escrow.escrow));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let addresses = [
+            payer.pubkey(),
+            escrow.mint_a,
+            escrow.mint_b,
+            escrow.maker_ata_a,
+            escrow.escrow,
+            escrow.vault,
+        ];
+        let saved = fixtures::snapshot(&program, &addresses, &dir);
+        assert_eq!(saved.len(), addresses.len());
+
+        // Round trip through the JSON format
+        let fixture = fixtures::AccountFixture::read(&dir.join(format!("{}.json", escrow.escrow)));
+        assert_eq!(fixture.address(), escrow.escrow);
+        assert_eq!(fixture.to_account(), program.get_account(&escrow.escrow).unwrap());
+
+        // Replay the captured escrow in a fresh SVM with no history
+        let (mut restored, _) = setup();
+        let mut loaded = fixtures::load_dir(&mut restored, &dir);
+        loaded.sort();
+        let mut expected = addresses.to_vec();
+        expected.sort();
+        assert_eq!(loaded, expected);
+
+        let escrow_account = restored.get_account(&escrow.escrow).unwrap();
+        let escrow_data = Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+        assert_eq!(escrow_data.maker, payer.pubkey());
+        assert_eq!(escrow_data.unlock_time, 1_000_000 + 1800);
+
+        set_clock(&mut restored, 1_000_000 + 1800);
+        take_escrow(&mut restored, &payer, &escrow).unwrap();
+
+        // Taking closes the escrow, so a second snapshot drops its fixture
+        let saved = fixtures::snapshot(&restored, &addresses, &dir);
+        assert!(!saved.contains(&escrow.escrow));
+        assert!(!dir.join(format!("{}.json", escrow.escrow)).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_checked_in_fixtures() {
+        let (mut program, _) = setup();
+
+        let dir = fixtures::fixtures_dir();
+        let mut escrows = 0;
+        for address in fixtures::load_dir(&mut program, &dir) {
+            let account = program.get_account(&address).unwrap();
+            if account.owner != PROGRAM_ID {
+                continue;
+            }
+
+            if Escrow::try_deserialize(&mut account.data.as_ref()).is_ok() {
+                escrows += 1;
+            } else {
+                PairRegistry::try_deserialize(&mut account.data.as_ref())
+                    .unwrap_or_else(|e| panic!("Fixture {} is not a valid escrow or registry: {:?}", address, e));
+            }
+        }
+        assert!(escrows > 0, "No escrow in the checked-in fixtures");
+    }
+
+    // Loads the checked-in escrow. Its maker's key isn't in the dump, so the
+    // SVM skips signature checks.
+    fn load_fixture_escrow() -> (LiteSVM, Escrow, TestEscrow) {
+        let (program, _) = setup();
+        let mut program = program.with_sigverify(false);
+
+        let loaded = fixtures::load_dir(&mut program, &fixtures::fixtures_dir());
+        let (address, escrow_data) = loaded
+            .iter()
+            .find_map(|address| {
+                let account = program.get_account(address).filter(|account| account.owner == PROGRAM_ID)?;
+                let escrow_data = Escrow::try_deserialize(&mut account.data.as_ref()).ok()?;
+                Some((*address, escrow_data))
+            })
+            .expect("No escrow in the checked-in fixtures");
+
+        let escrow = TestEscrow {
+            maker: escrow_data.maker,
+            mint_a: escrow_data.mint_a,
+            mint_b: escrow_data.mint_b,
+            maker_ata_a: associated_token::get_associated_token_address(&escrow_data.maker, &escrow_data.mint_a),
+            escrow: address,
+            vault: associated_token::get_associated_token_address(&address, &escrow_data.mint_a),
+        };
+        set_clock(&mut program, escrow_data.unlock_time);
+
+        (program, escrow_data, escrow)
+    }
+
+    #[test]
+    fn test_take_checked_in_escrow() {
+        let (mut program, escrow_data, escrow) = load_fixture_escrow();
+        let deposit = token_amount(&program, &escrow.vault);
+        assert_eq!(
+            read_registry(&program, &escrow.mint_a, &escrow.mint_b).entries,
+            vec![PairEntry::new(escrow.escrow, &escrow_data, deposit)]
+        );
+
+        // Nobody here holds the mint authority, so the taker's mint B is written directly
+        let taker = Keypair::new();
+        program.airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &escrow.mint_b);
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: escrow.mint_b,
+            owner: taker.pubkey(),
+            amount: escrow_data.receive,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }.pack_into_slice(&mut data);
+        program.set_account(taker_ata_b, Account {
+            lamports: program.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &escrow.mint_a);
+        let maker_ata_b = associated_token::get_associated_token_address(&escrow.maker, &escrow.mint_b);
+        let take_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Take {
+                taker: taker.pubkey(),
+                maker: escrow.maker,
+                mint_a: escrow.mint_a,
+                mint_b: escrow.mint_b,
+                taker_ata_a,
+                taker_ata_b,
+                maker_ata_b,
+                escrow: escrow.escrow,
+                registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+                vault: escrow.vault,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::Take { expected_receive: escrow_data.receive, min_give: deposit }.data(),
+        };
+        let tx = Transaction::new(&[&taker], Message::new(&[take_ix], Some(&taker.pubkey())), program.latest_blockhash());
+        program.send_transaction(tx).expect("Take of the checked-in escrow failed");
+
+        assert_eq!(token_amount(&program, &taker_ata_a), deposit);
+        assert_eq!(token_amount(&program, &maker_ata_b), escrow_data.receive);
+        assert!(is_closed(&program, &escrow.escrow));
+        assert!(read_registry(&program, &escrow.mint_a, &escrow.mint_b).entries.is_empty());
+    }
+
+    #[test]
+    fn test_refund_checked_in_escrow() {
+        let (mut program, _, escrow) = load_fixture_escrow();
+        let deposit = token_amount(&program, &escrow.vault);
+        let maker_balance = token_amount(&program, &escrow.maker_ata_a);
+
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Refund {
+                maker: escrow.maker,
+                mint_a: escrow.mint_a,
+                maker_ata_a: escrow.maker_ata_a,
+                escrow: escrow.escrow,
+                registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+                vault: escrow.vault,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::Refund {}.data(),
+        };
+
+        // Signed by nobody, the SVM doesn't verify signatures
+        let message = Message::new_with_blockhash(&[refund_ix], Some(&escrow.maker), &program.latest_blockhash());
+        program.send_transaction(Transaction::new_unsigned(message))
+            .expect("Refund of the checked-in escrow failed");
+
+        assert_eq!(token_amount(&program, &escrow.maker_ata_a), maker_balance + deposit);
+        assert!(is_closed(&program, &escrow.escrow));
+        assert!(read_registry(&program, &escrow.mint_a, &escrow.mint_b).entries.is_empty());
+    }
+
+    fn assert_escrow_error(result: Result<TransactionMetadata, String>, error: crate::error::EscrowError) {
+        let err = result.expect_err("Expected the transaction to fail");
+        let code = format!("Custom({})", u32::from(error));
+        assert!(err.contains(&code), "Expected {}, got {}", code, err);
+    }
+
+    #[test]
+    fn test_take_rejects_changed_terms() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 0, None);
+        result.unwrap();
+
+        // Taker quoted a different price than the escrow asks for
+        let result = take_escrow_with_terms(&mut program, &payer, &escrow, 9, 10);
+        assert_escrow_error(result, crate::error::EscrowError::UnexpectedReceive);
+
+        // Simulate the maker amending the price after the taker saw the offer
+        let mut escrow_account = program.get_account(&escrow.escrow).unwrap();
+        let mut escrow_data = Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+        escrow_data.receive = 20;
+        let mut data = Vec::with_capacity(escrow_account.data.len());
+        escrow_data.try_serialize(&mut data).unwrap();
+        data.resize(escrow_account.data.len(), 0);
+        escrow_account.data = data;
+        program.set_account(escrow.escrow, escrow_account).unwrap();
+        program.expire_blockhash();
+
+        let result = take_escrow_with_terms(&mut program, &payer, &escrow, 10, 10);
+        assert_escrow_error(result, crate::error::EscrowError::UnexpectedReceive);
+
+        program.expire_blockhash();
+        take_escrow_with_terms(&mut program, &payer, &escrow, 20, 10).unwrap();
+    }
+
+    #[test]
+    fn test_take_rejects_vault_below_minimum() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 0, None);
+        result.unwrap();
+
+        // Simulate the vault balance dropping below what the taker saw
+        let mut vault_account = program.get_account(&escrow.vault).unwrap();
+        let mut vault_data = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+        vault_data.amount = 7;
+        spl_token::state::Account::pack(vault_data, &mut vault_account.data).unwrap();
+        program.set_account(escrow.vault, vault_account).unwrap();
+
+        let result = take_escrow_with_terms(&mut program, &payer, &escrow, 10, 10);
+        assert_escrow_error(result, crate::error::EscrowError::VaultBelowMinimum);
+
+        // A taker who accepts the smaller amount can still take it
+        program.expire_blockhash();
+        let meta = take_escrow_with_terms(&mut program, &payer, &escrow, 10, 7).unwrap();
+        assert!(matches!(&escrow_events(&meta)[..], [EscrowView::Taken(e)] if e.amount_a == 7));
+    }
+
+    fn read_registry(program: &LiteSVM, mint_a: &Pubkey, mint_b: &Pubkey) -> PairRegistry {
+        let account = program.get_account(&pair_registry(mint_a, mint_b)).unwrap();
+        PairRegistry::try_deserialize(&mut account.data.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_pair_registry_lists_open_offers() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let mint_a = CreateMint::new(&mut program, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let mint_b = CreateMint::new(&mut program, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+
+        let other_maker = Keypair::new();
+        program.airdrop(&other_maker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let offer = |seed: u64, deposit: u64, receive: u64| crate::instruction::Make {
+            seed,
+            deposit,
+            receive,
+            lock_seconds: 0,
+            expires_at: None,
+            curve: None,
+        };
+
+        let (first, result) = make_offer(&mut program, &payer, &payer, &mint_a, &mint_b, offer(1, 10, 10));
+        result.unwrap();
+        let (second, result) = make_offer(&mut program, &payer, &other_maker, &mint_a, &mint_b, offer(7, 20, 15));
+        result.unwrap();
+
+        // One fetch of the pair registry shows both offers and their prices
+        let registry = read_registry(&program, &mint_a, &mint_b);
+        assert_eq!(registry.mint_a, mint_a);
+        assert_eq!(registry.mint_b, mint_b);
+        let entry = |escrow: Pubkey, maker: Pubkey, deposit: u64, receive: u64| PairEntry {
+            escrow,
+            maker,
+            deposit,
+            receive,
+            unlock_time: 1_000_000,
+            expires_at: None,
+            curve: None,
+        };
+        assert_eq!(registry.entries, vec![
+            entry(first.escrow, payer.pubkey(), 10, 10),
+            entry(second.escrow, other_maker.pubkey(), 20, 15),
+        ]);
+
+        // The reverse pair is a different registry
+        assert!(program.get_account(&pair_registry(&mint_b, &mint_a)).is_none());
+
+        take_escrow(&mut program, &payer, &first).unwrap();
+        let registry = read_registry(&program, &mint_a, &mint_b);
+        assert_eq!(registry.entries.len(), 1);
+        assert_eq!(registry.entries[0].escrow, second.escrow);
+
+        refund_escrow(&mut program, &other_maker, &second).unwrap();
+        let registry = read_registry(&program, &mint_a, &mint_b);
+        assert!(registry.entries.is_empty());
+    }
+
+    #[test]
+    fn test_make_with_full_pair_registry() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let mint_a = CreateMint::new(&mut program, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let mint_b = CreateMint::new(&mut program, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+
+        let offer = |program: &mut LiteSVM, deposit: u64| {
+            let maker = Keypair::new();
+            program.airdrop(&maker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+            make_offer(program, &payer, &maker, &mint_a, &mint_b, crate::instruction::Make {
+                seed: 0,
+                deposit,
+                receive: 10,
+                lock_seconds: 0,
+                expires_at: None,
+                curve: None,
+            })
+        };
+
+        // Offers fill every slot for the pair
+        let mut listed = Vec::new();
+        for _ in 0..crate::constants::MAX_PAIR_ENTRIES {
+            let (escrow, result) = offer(&mut program, 1);
+            result.unwrap();
+            listed.push(escrow);
+        }
+        assert_eq!(read_registry(&program, &mint_a, &mint_b).entries.len(), crate::constants::MAX_PAIR_ENTRIES);
+
+        // The next one fails instead of dropping a live offer, whatever its size
+        let (rejected, result) = offer(&mut program, 10);
+        assert_escrow_error(result, crate::error::EscrowError::RegistryFull);
+        assert!(program.get_account(&rejected.escrow).is_none());
+        let registry = read_registry(&program, &mint_a, &mint_b);
+        assert!(listed.iter().all(|escrow| registry.entries.iter().any(|entry| entry.escrow == escrow.escrow)));
+
+        // Taking an offer frees its slot
+        take_escrow_with_terms(&mut program, &payer, &listed[0], 10, 1).unwrap();
+        let (_, result) = offer(&mut program, 10);
+        result.expect("Make should succeed once a slot is free");
+    }
+
+    fn counter_pda(escrow: &Pubkey, taker: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"counter", escrow.as_ref(), taker.as_ref()], &PROGRAM_ID).0
+    }
+
+    // Funds a new taker with Mint B (minted by `payer`) and sends a CounterOffer instruction
+    fn counter_offer(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        escrow: &TestEscrow,
+        receive: u64,
+    ) -> (Keypair, Result<TransactionMetadata, String>) {
+        let taker = Keypair::new();
+        program.airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let taker_ata_b = CreateAssociatedTokenAccount::new(program, &taker, &escrow.mint_b)
+            .owner(&taker.pubkey()).send().unwrap();
+        MintTo::new(program, payer, &escrow.mint_b, &taker_ata_b, 1_000).send().unwrap();
+
+        let counter = counter_pda(&escrow.escrow, &taker.pubkey());
+
+        let counter_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::CounterOffer {
+                taker: taker.pubkey(),
+                maker: escrow.maker,
+                mint_a: escrow.mint_a,
+                mint_b: escrow.mint_b,
+                taker_ata_b,
+                escrow: escrow.escrow,
+                counter,
+                counter_vault: associated_token::get_associated_token_address(&counter, &escrow.mint_b),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::CounterOffer { receive }.data(),
+        };
+
+        let message = Message::new(&[counter_ix], Some(&taker.pubkey()));
+        let tx = Transaction::new(&[&taker], message, program.latest_blockhash());
+        let result = program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err));
+
+        (taker, result)
+    }
+
+    fn accept_counter(
+        program: &mut LiteSVM,
+        maker: &Keypair,
+        escrow: &TestEscrow,
+        taker: &Pubkey,
+    ) -> Result<TransactionMetadata, String> {
+        let counter = counter_pda(&escrow.escrow, taker);
+
+        let accept_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AcceptCounter {
+                maker: maker.pubkey(),
+                taker: *taker,
+                mint_a: escrow.mint_a,
+                mint_b: escrow.mint_b,
+                taker_ata_a: associated_token::get_associated_token_address(taker, &escrow.mint_a),
+                maker_ata_b: associated_token::get_associated_token_address(&maker.pubkey(), &escrow.mint_b),
+                escrow: escrow.escrow,
+                counter,
+                registry: pair_registry(&escrow.mint_a, &escrow.mint_b),
+                vault: escrow.vault,
+                counter_vault: associated_token::get_associated_token_address(&counter, &escrow.mint_b),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::AcceptCounter {}.data(),
+        };
+
+        let message = Message::new(&[accept_ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[maker], message, program.latest_blockhash());
+        program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err))
+    }
+
+    fn refund_counter(program: &mut LiteSVM, taker: &Keypair, escrow: &TestEscrow) -> Result<TransactionMetadata, String> {
+        let counter = counter_pda(&escrow.escrow, &taker.pubkey());
+
+        let refund_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::RefundCounter {
+                taker: taker.pubkey(),
+                mint_b: escrow.mint_b,
+                taker_ata_b: associated_token::get_associated_token_address(&taker.pubkey(), &escrow.mint_b),
+                counter,
+                counter_vault: associated_token::get_associated_token_address(&counter, &escrow.mint_b),
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::RefundCounter {}.data(),
+        };
+
+        let message = Message::new(&[refund_ix], Some(&taker.pubkey()));
+        let tx = Transaction::new(&[taker], message, program.latest_blockhash());
+        program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err))
+    }
+
+    fn token_amount(program: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = program.get_account(token_account).unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    fn is_closed(program: &LiteSVM, address: &Pubkey) -> bool {
+        program.get_account(address).map_or(true, |a| a.lamports == 0)
+    }
+
+    #[test]
+    fn test_accept_counter_settles_both_sides() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 1800, None);
+        result.unwrap();
+
+        // Two prospective takers each counter with a lower price
+        let (taker, result) = counter_offer(&mut program, &payer, &escrow, 8);
+        let events = escrow_events(&result.unwrap());
+        assert!(matches!(&events[..], [EscrowView::CounterOffered(e)] if e.receive == 8 && e.escrow == escrow.escrow));
+
+        let (other_taker, result) = counter_offer(&mut program, &payer, &escrow, 9);
+        result.unwrap();
+
+        let counter = counter_pda(&escrow.escrow, &taker.pubkey());
+        let counter_vault = associated_token::get_associated_token_address(&counter, &escrow.mint_b);
+        assert_eq!(token_amount(&program, &counter_vault), 8);
+
+        // The maker can settle a counter even while the escrow is locked
+        let meta = accept_counter(&mut program, &payer, &escrow, &taker.pubkey()).unwrap();
+        let events = escrow_events(&meta);
+        assert!(matches!(&events[..], [EscrowView::CounterAccepted(e)] if e.amount_a == 10 && e.amount_b == 8));
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &escrow.mint_a);
+        let maker_ata_b = associated_token::get_associated_token_address(&payer.pubkey(), &escrow.mint_b);
+        assert_eq!(token_amount(&program, &taker_ata_a), 10);
+        assert_eq!(token_amount(&program, &maker_ata_b), 8);
+
+        assert!(is_closed(&program, &escrow.escrow));
+        assert!(is_closed(&program, &escrow.vault));
+        assert!(is_closed(&program, &counter));
+        assert!(is_closed(&program, &counter_vault));
+        assert!(read_registry(&program, &escrow.mint_a, &escrow.mint_b).entries.is_empty());
+
+        // The losing counter-offer can still be withdrawn after the escrow is gone
+        let meta = refund_counter(&mut program, &other_taker, &escrow).unwrap();
+        let events = escrow_events(&meta);
+        assert!(matches!(&events[..], [EscrowView::CounterRefunded(e)] if e.amount == 9));
+
+        let other_taker_ata_b = associated_token::get_associated_token_address(&other_taker.pubkey(), &escrow.mint_b);
+        assert_eq!(token_amount(&program, &other_taker_ata_b), 1_000);
+        assert!(is_closed(&program, &counter_pda(&escrow.escrow, &other_taker.pubkey())));
+    }
+
+    #[test]
+    fn test_counter_offer_rejects_invalid_terms() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 0, Some(1_000_000 + 3600));
+        result.unwrap();
+
+        // Same price as the escrow, the taker should just take it
+        let (_, result) = counter_offer(&mut program, &payer, &escrow, 10);
+        assert_escrow_error(result, crate::error::EscrowError::InvalidCounter);
+
+        let (_, result) = counter_offer(&mut program, &payer, &escrow, 0);
+        assert_escrow_error(result, crate::error::EscrowError::InvalidCounter);
+
+        set_clock(&mut program, 1_000_000 + 3600);
+        let (_, result) = counter_offer(&mut program, &payer, &escrow, 8);
+        assert_escrow_error(result, crate::error::EscrowError::EscrowExpired);
+    }
+
+    #[test]
+    fn test_only_maker_can_accept_counter() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_escrow(&mut program, &payer, 1, 0, None);
+        result.unwrap();
+
+        let (taker, result) = counter_offer(&mut program, &payer, &escrow, 8);
+        result.unwrap();
+
+        // The counter-offerer can't accept their own counter
+        let result = accept_counter(&mut program, &taker, &escrow, &taker.pubkey());
+        assert!(result.is_err());
+        assert_eq!(token_amount(&program, &escrow.vault), 10);
+
+        refund_counter(&mut program, &taker, &escrow).unwrap();
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &escrow.mint_b);
+        assert_eq!(token_amount(&program, &taker_ata_b), 1_000);
+    }
+
+    fn nft_metadata(mint: &Pubkey, collection: Option<(Pubkey, bool)>) -> MetadataPrefix {
+        MetadataPrefix {
+            key: MetadataPrefix::METADATA_V1,
+            update_authority: Pubkey::new_unique(),
+            mint: *mint,
+            name: "Escrowed #1".to_string(),
+            symbol: "ESC".to_string(),
+            uri: "https://example.com/1.json".to_string(),
+            seller_fee_basis_points: 500,
+            creators: None,
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: Some(255),
+            token_standard: Some(0),
+            collection: collection.map(|(key, verified)| Collection { verified, key }),
+        }
+    }
+
+    // Writes a metadata account for `mint` as if the metadata program had created it
+    fn set_metadata(program: &mut LiteSVM, mint: &Pubkey, metadata: &MetadataPrefix) -> Pubkey {
+        let address = Pubkey::find_program_address(
+            &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+            &TOKEN_METADATA_PROGRAM_ID,
+        ).0;
+
+        let mut data = Vec::new();
+        metadata.serialize(&mut data).unwrap();
+        // Real metadata accounts are fixed size with trailing fields and padding
+        data.resize(679, 0);
+
+        program.set_account(address, Account {
+            lamports: program.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: TOKEN_METADATA_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+
+        address
+    }
+
+    // Mints `supply` of a new mint A to the maker and sends a MakeNft instruction
+    fn make_nft_escrow(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        decimals: u8,
+        supply: u64,
+        collection: Option<(Pubkey, bool)>,
+        expected_collection: Pubkey,
+    ) -> (TestEscrow, Result<TransactionMetadata, String>) {
+        let maker = payer.pubkey();
+
+        let mint_a = CreateMint::new(program, payer).decimals(decimals).authority(&maker).send().unwrap();
+        let mint_b = CreateMint::new(program, payer).decimals(6).authority(&maker).send().unwrap();
+
+        let maker_ata_a = CreateAssociatedTokenAccount::new(program, payer, &mint_a)
+            .owner(&maker).send().unwrap();
+        MintTo::new(program, payer, &mint_a, &maker_ata_a, supply).send().unwrap();
+
+        let metadata = set_metadata(program, &mint_a, &nft_metadata(&mint_a, collection));
+
+        let seed = 1u64;
+        let escrow = Pubkey::find_program_address(
+            &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+            &PROGRAM_ID,
+        ).0;
+        let vault = associated_token::get_associated_token_address(&escrow, &mint_a);
+
+        let make_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::MakeNft {
+                maker,
+                mint_a,
+                mint_b,
+                metadata,
+                maker_ata_a,
+                escrow,
+                registry: pair_registry(&mint_a, &mint_b),
+                vault,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                event_authority: event_authority(),
+                program: PROGRAM_ID,
+            }.to_account_metas(None),
+            data: crate::instruction::MakeNft {
+                seed,
+                receive: 500,
+                lock_seconds: 0,
+                expires_at: None,
+                collection: expected_collection,
+            }.data(),
+        };
+
+        let message = Message::new(&[make_ix], Some(&maker));
+        let tx = Transaction::new(&[payer], message, program.latest_blockhash());
+        let result = program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err));
+
+        (TestEscrow { maker, mint_a, mint_b, maker_ata_a, escrow, vault }, result)
+    }
+
+    #[test]
+    fn test_make_nft_with_verified_collection() {
+        let (mut program, payer) = setup();
+        let collection = Pubkey::new_unique();
+
+        let (escrow, result) = make_nft_escrow(&mut program, &payer, 0, 1, Some((collection, true)), collection);
+        let meta = result.unwrap();
+        msg!("MakeNft CUs Consumed: {}", meta.compute_units_consumed);
+
+        assert_eq!(token_amount(&program, &escrow.vault), 1);
+        assert_eq!(token_amount(&program, &escrow.maker_ata_a), 0);
+
+        let registry = read_registry(&program, &escrow.mint_a, &escrow.mint_b);
+        assert_eq!(registry.entries[0].deposit, 1);
+        assert_eq!(registry.entries[0].receive, 500);
+    }
+
+    #[test]
+    fn test_make_nft_rejects_wrong_collection() {
+        let collection = Pubkey::new_unique();
+
+        // Different collection
+        let (mut program, payer) = setup();
+        let (_, result) = make_nft_escrow(&mut program, &payer, 0, 1, Some((Pubkey::new_unique(), true)), collection);
+        assert_escrow_error(result, crate::error::EscrowError::CollectionMismatch);
+
+        // Right collection but not verified by its authority
+        let (mut program, payer) = setup();
+        let (_, result) = make_nft_escrow(&mut program, &payer, 0, 1, Some((collection, false)), collection);
+        assert_escrow_error(result, crate::error::EscrowError::CollectionMismatch);
+
+        // No collection at all
+        let (mut program, payer) = setup();
+        let (_, result) = make_nft_escrow(&mut program, &payer, 0, 1, None, collection);
+        assert_escrow_error(result, crate::error::EscrowError::CollectionMismatch);
+    }
+
+    #[test]
+    fn test_make_nft_rejects_fungible_mint() {
+        let collection = Pubkey::new_unique();
+
+        let (mut program, payer) = setup();
+        let (_, result) = make_nft_escrow(&mut program, &payer, 6, 1_000, Some((collection, true)), collection);
+        assert_escrow_error(result, crate::error::EscrowError::NotAnNft);
+
+        // Decimals 0 but more than one minted
+        let (mut program, payer) = setup();
+        let (_, result) = make_nft_escrow(&mut program, &payer, 0, 2, Some((collection, true)), collection);
+        assert_escrow_error(result, crate::error::EscrowError::NotAnNft);
+    }
+
+    // Linear curve from 100 to 20 Mint B over 1000 seconds, starting at 1_000_000
+    fn auction_curve() -> PriceCurve {
+        PriceCurve {
+            start_receive: 100,
+            end_receive: 20,
+            start_time: 1_000_000,
+            end_time: 1_000_000 + 1_000,
+        }
+    }
+
+    fn make_auction(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        receive: u64,
+        curve: PriceCurve,
+    ) -> (TestEscrow, Result<TransactionMetadata, String>) {
+        let maker = payer.pubkey();
+
+        let mint_a = CreateMint::new(program, payer).decimals(6).authority(&maker).send().unwrap();
+        let mint_b = CreateMint::new(program, payer).decimals(6).authority(&maker).send().unwrap();
+
+        make_offer(program, payer, payer, &mint_a, &mint_b, crate::instruction::Make {
+            deposit: 10,
+            seed: 1,
+            receive,
+            lock_seconds: 0,
+            expires_at: None,
+            curve: Some(curve),
+        })
+    }
+
+    #[test]
+    fn test_price_curve_rounds_up_and_floors() {
+        let curve = PriceCurve {
+            start_receive: 100,
+            end_receive: 10,
+            start_time: 1_000,
+            end_time: 1_007,
+        };
+
+        assert_eq!(curve.price_at(0), 100);
+        assert_eq!(curve.price_at(1_000), 100);
+        // 100 - 90/7 = 87.14..., rounded up for the maker
+        assert_eq!(curve.price_at(1_001), 88);
+        // 100 - 90*6/7 = 22.85...
+        assert_eq!(curve.price_at(1_006), 23);
+        assert_eq!(curve.price_at(1_007), 10);
+        assert_eq!(curve.price_at(i64::MAX), 10);
+
+        // Never increases and never drops below the floor
+        let mut last = curve.start_receive;
+        for now in 990..1_020 {
+            let price = curve.price_at(now);
+            assert!(price <= last && price >= curve.end_receive);
+            last = price;
+        }
+    }
+
+    #[test]
+    fn test_take_follows_price_curve() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_auction(&mut program, &payer, 100, auction_curve());
+        result.unwrap();
+
+        let escrow_account = program.get_account(&escrow.escrow).unwrap();
+        let escrow_data = Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+        assert_eq!(escrow_data.curve, Some(auction_curve()));
+
+        // The registry lists the curve, so its price tracks the escrow's
+        let entry = read_registry(&program, &escrow.mint_a, &escrow.mint_b).entries[0];
+        assert_eq!(entry.curve, Some(auction_curve()));
+        assert!(entry.is_open(1_000_000));
+        for now in [1_000_000, 1_000_000 + 500, 1_000_000 + 5_000] {
+            assert_eq!(entry.current_receive(now), escrow_data.current_receive(now));
+        }
+        assert_eq!(entry.current_receive(1_000_000 + 500), 60);
+
+        // Halfway down the curve the ask is 60
+        set_clock(&mut program, 1_000_000 + 500);
+
+        // A taker quoting below the current price is rejected
+        let result = take_escrow_with_terms(&mut program, &payer, &escrow, 59, 10);
+        assert_escrow_error(result, crate::error::EscrowError::UnexpectedReceive);
+
+        // A taker willing to pay the starting price only pays the current one
+        program.expire_blockhash();
+        let meta = take_escrow_with_terms(&mut program, &payer, &escrow, 100, 10).unwrap();
+        assert!(matches!(&escrow_events(&meta)[..], [EscrowView::Taken(e)] if e.amount_b == 60));
+
+        let maker_ata_b = associated_token::get_associated_token_address(&payer.pubkey(), &escrow.mint_b);
+        assert_eq!(token_amount(&program, &maker_ata_b), 60);
+    }
+
+    #[test]
+    fn test_take_after_curve_end_pays_floor() {
+        let (mut program, payer) = setup();
+        set_clock(&mut program, 1_000_000);
+
+        let (escrow, result) = make_auction(&mut program, &payer, 100, auction_curve());
+        result.unwrap();
+
+        set_clock(&mut program, 1_000_000 + 5_000);
+        take_escrow_with_terms(&mut program, &payer, &escrow, 20, 10).unwrap();
+
+        let maker_ata_b = associated_token::get_associated_token_address(&payer.pubkey(), &escrow.mint_b);
+        assert_eq!(token_amount(&program, &maker_ata_b), 20);
+    }
+
+    #[test]
+    fn test_make_rejects_invalid_curve() {
+        let invalid = [
+            // Starting price differs from `receive`
+            (90, auction_curve()),
+            // Price doesn't decay
+            (100, PriceCurve { end_receive: 100, ..auction_curve() }),
+            // Zero floor
+            (100, PriceCurve { end_receive: 0, ..auction_curve() }),
+            // Empty period
+            (100, PriceCurve { end_time: 1_000_000, ..auction_curve() }),
+        ];
+
+        for (receive, curve) in invalid {
+            let (mut program, payer) = setup();
+            set_clock(&mut program, 1_000_000);
+
+            let (_, result) = make_auction(&mut program, &payer, receive, curve);
+            assert_escrow_error(result, crate::error::EscrowError::InvalidPriceCurve);
+        }
+    }
+
+    // Three makers offering 10 Mint A each for the same pair, asking 10, 30 and 20 Mint B
+    fn make_pair_offers(program: &mut LiteSVM, payer: &Keypair) -> Vec<TestEscrow> {
+        let mint_a = CreateMint::new(program, payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let mint_b = CreateMint::new(program, payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+
+        [10, 30, 20].into_iter().map(|receive| {
+            let maker = Keypair::new();
+            program.airdrop(&maker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+            let (escrow, result) = make_offer(program, payer, &maker, &mint_a, &mint_b, crate::instruction::Make {
+                deposit: 10,
+                seed: 1,
+                receive,
+                lock_seconds: 0,
+                expires_at: None,
+                curve: None,
+            });
+            result.unwrap();
+            escrow
+        }).collect()
+    }
+
+    // Funds a taker with Mint B and sends a TakeMany over `escrows`, in order
+    fn take_many(
+        program: &mut LiteSVM,
+        payer: &Keypair,
+        escrows: &[&TestEscrow],
+        max_spend: u64,
+    ) -> (Keypair, Result<TransactionMetadata, String>) {
+        let (mint_a, mint_b) = (escrows[0].mint_a, escrows[0].mint_b);
+        let taker = Keypair::new();
+        program.airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        let taker_ata_b = CreateAssociatedTokenAccount::new(program, &taker, &mint_b)
+            .owner(&taker.pubkey()).send().unwrap();
+        MintTo::new(program, payer, &mint_b, &taker_ata_b, 1_000).send().unwrap();
+
+        let mut accounts = crate::accounts::TakeMany {
+            taker: taker.pubkey(),
+            mint_a,
+            mint_b,
+            taker_ata_a,
+            taker_ata_b,
+            registry: pair_registry(&mint_a, &mint_b),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }.to_account_metas(None);
+        for escrow in escrows {
+            accounts.extend([
+                AccountMeta::new(escrow.escrow, false),
+                AccountMeta::new(escrow.vault, false),
+                AccountMeta::new(escrow.maker, false),
+                AccountMeta::new(associated_token::get_associated_token_address(&escrow.maker, &mint_b), false),
+            ]);
+        }
+
+        let take_many_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: crate::instruction::TakeMany { count: escrows.len() as u8, max_spend }.data(),
+        };
+
+        let message = Message::new(&[take_many_ix], Some(&taker.pubkey()));
+        let tx = Transaction::new(&[&taker], message, program.latest_blockhash());
+        let result = program.send_transaction(tx)
+            .map_err(|e| format!("{:?}", e.err));
+
+        (taker, result)
+    }
+
+    #[test]
+    fn test_take_many_stops_at_max_spend() {
+        let (mut program, payer) = setup();
+        let offers = make_pair_offers(&mut program, &payer);
+        let (mint_a, mint_b) = (offers[0].mint_a, offers[0].mint_b);
+
+        // 10 fits, 30 doesn't fit in the 25 left, 20 does
+        let (taker, result) = take_many(&mut program, &payer, &[&offers[0], &offers[1], &offers[2]], 35);
+        let meta = result.unwrap();
+        msg!("take_many filling 2 of 3 escrows, CUs Consumed: {}", meta.compute_units_consumed);
+
+        let taken = escrow_events(&meta).into_iter().map(|event| match event {
+            EscrowView::Taken(e) => (e.escrow, e.amount_b),
+            other => panic!("Expected EscrowTaken, got {:?}", other),
+        }).collect::<Vec<_>>();
+        assert_eq!(taken, vec![(offers[0].escrow, 10), (offers[2].escrow, 20)]);
+
+        let taker_ata_a = associated_token::get_associated_token_address(&taker.pubkey(), &mint_a);
+        let taker_ata_b = associated_token::get_associated_token_address(&taker.pubkey(), &mint_b);
+        assert_eq!(token_amount(&program, &taker_ata_a), 20);
+        assert_eq!(token_amount(&program, &taker_ata_b), 1_000 - 30);
+
+        for (offer, paid) in [(&offers[0], 10), (&offers[2], 20)] {
+            let maker_ata_b = associated_token::get_associated_token_address(&offer.maker, &mint_b);
+            assert_eq!(token_amount(&program, &maker_ata_b), paid);
+            assert!(is_closed(&program, &offer.escrow));
+            assert!(is_closed(&program, &offer.vault));
+        }
+
+        // The skipped offer is untouched and still listed
+        assert_eq!(token_amount(&program, &offers[1].vault), 10);
+        let registry = read_registry(&program, &mint_a, &mint_b);
+        assert_eq!(registry.entries.len(), 1);
+        assert_eq!(registry.entries[0].escrow, offers[1].escrow);
+    }
+
+    #[test]
+    fn test_take_many_compute_units() {
+        let (mut program, payer) = setup();
+        let offers = make_pair_offers(&mut program, &payer);
+
+        let single = take_escrow_with_terms(&mut program, &payer, &offers[0], 10, 10)
+            .unwrap().compute_units_consumed;
+
+        let (_, result) = take_many(&mut program, &payer, &[&offers[1], &offers[2]], u64::MAX);
+        let batch = result.unwrap().compute_units_consumed;
+
+        msg!("take: {} CUs", single);
+        msg!("take_many of 2: {} CUs ({} per escrow)", batch, batch / 2);
+        assert!(offers.iter().all(|offer| is_closed(&program, &offer.escrow)));
+
+        // Each escrow in a batch should cost about as much as a single take
+        assert!(batch / 2 < single * 3 / 2, "take_many costs {} per escrow, take costs {}", batch / 2, single);
+    }
+
+    #[test]
+    fn test_take_many_rejects_invalid_batch() {
+        let (mut program, payer) = setup();
+        let offers = make_pair_offers(&mut program, &payer);
+
+        // Vault of another escrow
+        let mismatched = TestEscrow { vault: offers[1].vault, ..offers[0].clone() };
+        let (_, result) = take_many(&mut program, &payer, &[&mismatched], u64::MAX);
+        assert_escrow_error(result, crate::error::EscrowError::InvalidBatch);
+
+        // Nothing fits in the budget
+        let (_, result) = take_many(&mut program, &payer, &[&offers[1], &offers[2]], 5);
+        assert_escrow_error(result, crate::error::EscrowError::NothingTaken);
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_hook, StateWithExtensions},
+    },
+    token_interface::{transfer_checked, Mint, TokenInterface, TransferChecked},
+};
+use spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi;
+
+/// Transfer hook program configured on a Token-2022 mint, if any
+pub fn transfer_hook_program_id(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    Ok(transfer_hook::get_program_id(&mint_state))
+}
+
+/// `transfer_checked` that also works for mints with a transfer hook.
+///
+/// `remaining_accounts` must hold the hook program, its ExtraAccountMetaList
+/// and every account the list resolves to. They are looked up by key, so the
+/// same slice can be passed for each transfer in an instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+
+    let Some(hook_program_id) = transfer_hook_program_id(&mint_info)? else {
+        let cpi_accounts = TransferChecked {
+            from,
+            to,
+            authority,
+            mint: mint_info,
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        return transfer_checked(cpi_ctx, amount, mint.decimals);
+    };
+
+    // Build the base transfer_checked instruction
+    let mut transfer_ix = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        from.key,
+        mint_info.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        mint.decimals,
+    )?;
+
+    let mut cpi_account_infos = vec![
+        from.clone(),
+        mint_info.clone(),
+        to.clone(),
+        authority.clone(),
+        token_program.to_account_info(),
+    ];
+
+    // Add extra accounts for transfer hook CPI
+    add_extra_accounts_for_execute_cpi(
+        &mut transfer_ix,
+        &mut cpi_account_infos,
+        &hook_program_id,
+        from,
+        mint_info,
+        to,
+        authority,
+        amount,
+        remaining_accounts,
+    )?;
+
+    invoke_signed(&transfer_ix, &cpi_account_infos, signer_seeds)?;
+
+    Ok(())
+}
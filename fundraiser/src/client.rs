@@ -0,0 +1,36 @@
+//! Off-chain helpers for reading fundraiser state without sending a transaction.
+//!
+//! These decode raw account data and reuse the same `Fundraiser::status` logic the
+//! `GetStatus` instruction runs on chain, so both paths always agree.
+
+use crate::state::{Fundraiser, StatusReport};
+use crate::FundraiserInstructions;
+
+/// Decodes the packed `Fundraiser` struct from raw account data.
+pub fn decode_fundraiser(data: &[u8]) -> Option<Fundraiser> {
+    if data.len() < Fundraiser::LEN {
+        return None;
+    }
+    // `Fundraiser` is `repr(C, packed)`, so an unaligned read is always valid
+    Some(unsafe { core::ptr::read_unaligned(data.as_ptr() as *const Fundraiser) })
+}
+
+/// Computes the lifecycle status from the fundraiser account data at `current_time`.
+/// Pass `None` (or empty data) for an account that no longer exists.
+pub fn fundraiser_status(account_data: Option<&[u8]>, current_time: i64) -> Option<StatusReport> {
+    match account_data {
+        None => Some(StatusReport::FINALIZED),
+        Some(data) if data.is_empty() => Some(StatusReport::FINALIZED),
+        Some(data) => decode_fundraiser(data).map(|fundraiser| fundraiser.status(current_time)),
+    }
+}
+
+/// Decodes the return data produced by the `GetStatus` instruction.
+pub fn decode_status_return_data(data: &[u8]) -> Option<StatusReport> {
+    StatusReport::from_bytes(data)
+}
+
+/// Instruction data for `GetStatus`; the only account is the fundraiser PDA.
+pub fn get_status_ix_data() -> [u8; 1] {
+    [FundraiserInstructions::GetStatus as u8]
+}
@@ -2,4 +2,7 @@ pub const ANCHOR_DISCRIMINATOR: usize = 8;
 pub const MIN_AMOUNT_TO_RAISE: u64 = 3;
 pub const SECONDS_TO_DAYS: i64 = 86400;
 pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 100;
-pub const PERCENTAGE_SCALER: u64 = 100;
\ No newline at end of file
+pub const PERCENTAGE_SCALER: u64 = 100;
+pub const VOTING_PERIOD_SECONDS: i64 = 3 * SECONDS_TO_DAYS;
+pub const MAX_PROPOSALS: u64 = 3;
+pub const CRANK_BOUNTY_PERCENTAGE: u64 = 10;
\ No newline at end of file
@@ -12,6 +12,16 @@ pub enum FundraiserError {
     FundraiserEnded = 6,
     InvalidAmount = 7,
     NoContribution=8,
+    InvalidGovernanceConfig = 9,
+    ProposalActive = 10,
+    NoActiveProposal = 11,
+    VotingEnded = 12,
+    VotingNotEnded = 13,
+    AlreadyVoted = 14,
+    FinalizeApproved = 15,
+    InvalidProposal = 16,
+    ProposalLimitReached = 17,
+    InvalidMint = 18,
 }
 
 impl From<FundraiserError> for ProgramError {
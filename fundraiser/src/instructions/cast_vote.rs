@@ -0,0 +1,132 @@
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::{pubkey, ProgramResult};
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::rent::Rent;
+use pinocchio::sysvars::Sysvar;
+use pinocchio_system::instructions::CreateAccount;
+use crate::error::FundraiserError;
+use crate::state::{Contributor, Fundraiser, Proposal, VoteRecord};
+
+#[derive(Clone, Copy)]
+pub struct CastVoteIxData {
+    pub approve: u8,
+}
+
+impl CastVoteIxData {
+    pub const LEN: usize = size_of::<Self>();
+
+    #[inline(always)]
+    pub unsafe fn load_ix_data(bytes: &[u8]) -> Result<Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(*(bytes.as_ptr() as *const Self))
+    }
+}
+
+pub fn process_cast_vote(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [
+        contributor,
+        fundraiser,
+        contributor_account,
+        proposal,
+        vote_record,
+        _system_program,
+        rent_sysvar,
+        ..
+    ] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { CastVoteIxData::load_ix_data(data)? };
+
+    if !contributor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if fundraiser.owner() != &crate::ID || proposal.owner() != &crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
+    let proposal_data = Proposal::from_account_info(proposal)?;
+
+    if proposal_data.fundraiser != *fundraiser.key() {
+        return Err(FundraiserError::InvalidProposal.into());
+    }
+
+    if fundraiser_data.proposal_active == 0 {
+        return Err(FundraiserError::NoActiveProposal.into());
+    }
+
+    let current_time = Clock::get()?.unix_timestamp;
+    if current_time >= proposal_data.voting_ends_at {
+        return Err(FundraiserError::VotingEnded.into());
+    }
+
+    // Votes are weighted by the voter's contribution to this campaign
+    let campaign_id_bytes = fundraiser_data.campaign_id.to_le_bytes();
+    let (contributor_pda, _) = pubkey::find_program_address(
+        &[
+            b"contributor",
+            fundraiser.key().as_ref(),
+            contributor.key().as_ref(),
+            campaign_id_bytes.as_ref(),
+        ],
+        &crate::ID,
+    );
+    if contributor_pda != *contributor_account.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let weight = Contributor::from_account_info(contributor_account)?.amount;
+    if weight == 0 {
+        return Err(FundraiserError::NoContribution.into());
+    }
+
+    // One vote record per (proposal, contributor); its existence means they voted
+    if !vote_record.data_is_empty() {
+        return Err(FundraiserError::AlreadyVoted.into());
+    }
+
+    let seeds = &[b"vote", proposal.key().as_ref(), contributor.key().as_ref()];
+    let (pda_vote, bump) = pubkey::find_program_address(seeds, &crate::ID);
+
+    if pda_vote != *vote_record.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::from_account_info(rent_sysvar)?;
+
+    let bump_seed = [bump];
+    let vote_seeds: [Seed; 4] = [
+        Seed::from(b"vote"),
+        Seed::from(proposal.key().as_ref()),
+        Seed::from(contributor.key().as_ref()),
+        Seed::from(&bump_seed),
+    ];
+
+    CreateAccount {
+        from: contributor,
+        to: vote_record,
+        lamports: rent.minimum_balance(VoteRecord::LEN),
+        space: VoteRecord::LEN as u64,
+        owner: &crate::ID,
+    }.invoke_signed(&[Signer::from(&vote_seeds)])?;
+
+    let approve = ix_data.approve != 0;
+
+    let vote_data = VoteRecord::from_account_info(vote_record)?;
+    vote_data.weight = weight;
+    vote_data.approve = approve as u8;
+
+    if approve {
+        proposal_data.yes_votes += weight;
+    } else {
+        proposal_data.no_votes += weight;
+    }
+
+    Ok(())
+}
@@ -7,8 +7,9 @@ use pinocchio::{
     sysvars::clock::Clock,
     sysvars::Sysvar,
 };
+use pinocchio_system::instructions::Transfer as SystemTransfer;
 use pinocchio_token::instructions::Transfer;
-use crate::constants::{MAX_CONTRIBUTION_PERCENTAGE, PERCENTAGE_SCALER, SECONDS_TO_DAYS};
+use crate::constants::{MAX_CONTRIBUTION_PERCENTAGE, PERCENTAGE_SCALER};
 use crate::error::FundraiserError;
 use crate::state::{Fundraiser, Contributor};
 
@@ -61,10 +62,12 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
 
         let rent = Rent::from_account_info(rent_sysvar)?;
 
+        let campaign_id_bytes = fundraiser_data.campaign_id.to_le_bytes();
         let seeds = &[
             b"contributor",
             fundraiser.key().as_ref(),
             contributor.key().as_ref(),
+            campaign_id_bytes.as_ref(),
         ];
         let (pda, bump) = pubkey::find_program_address(seeds, &crate::ID);
 
@@ -73,10 +76,11 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         }
 
         let bump_seed = [bump];
-        let signer_seeds: [Seed; 4] = [
+        let signer_seeds: [Seed; 5] = [
             Seed::from(b"contributor"),
             Seed::from(fundraiser.key().as_ref()),
             Seed::from(contributor.key().as_ref()),
+            Seed::from(&campaign_id_bytes),
             Seed::from(&bump_seed),
         ];
         let signer = Signer::from(&signer_seeds);
@@ -107,8 +111,7 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
     }
 
     let current_time = Clock::get()?.unix_timestamp;
-    let elapsed_days = ((current_time - fundraiser_data.time_started) / SECONDS_TO_DAYS) as u8;
-    if elapsed_days > fundraiser_data.duration {
+    if fundraiser_data.has_ended(current_time) {
         return Err(FundraiserError::FundraiserEnded.into());
     }
 
@@ -118,14 +121,25 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
     }
 
     let amount = ix_data.amount;
-    let transfer_ix = Transfer {
-        from: contributor_ata,
-        to: vault,
-        authority: contributor,
-        amount
-    };
+    if fundraiser_data.is_sol() {
+        // contributor_ata is unused for SOL campaigns
+        fundraiser_data.check_sol_vault(fundraiser, vault)?;
 
-    transfer_ix.invoke()?;
+        SystemTransfer {
+            from: contributor,
+            to: vault,
+            lamports: amount,
+        }.invoke()?;
+    } else {
+        let transfer_ix = Transfer {
+            from: contributor_ata,
+            to: vault,
+            authority: contributor,
+            amount
+        };
+
+        transfer_ix.invoke()?;
+    }
 
     fundraiser_data.current_amount += amount;
     contributor_data.amount += amount;
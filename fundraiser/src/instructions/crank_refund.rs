@@ -0,0 +1,154 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey,
+    ProgramResult,
+    sysvars::clock::Clock,
+    sysvars::Sysvar,
+};
+use pinocchio_associated_token_account::instructions::CreateIdempotent;
+use pinocchio_token::instructions::Transfer;
+use crate::constants::{CRANK_BOUNTY_PERCENTAGE, PERCENTAGE_SCALER};
+use crate::error::FundraiserError;
+use crate::state::{Contributor, Fundraiser};
+
+/// Permissionless refund of a failed campaign on behalf of its contributors.
+///
+/// Remaining accounts are `(contributor wallet, contributor PDA, contributor ATA)`
+/// triples. Each contributor gets their contribution back (to the ATA, created if
+/// needed, or to the wallet for SOL campaigns) and their contributor PDA is closed
+/// with the rent returned to the wallet, minus a fixed `CRANK_BOUNTY_PERCENTAGE`
+/// bounty to the cranker.
+pub fn process_crank_refund(accounts: &[AccountInfo]) -> ProgramResult {
+    let [
+        cranker,
+        mint_to_raise,
+        fundraiser,
+        vault,
+        system_program,
+        token_program,
+        _associated_token_program,
+        remaining @ ..
+    ] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !cranker.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if remaining.is_empty() || remaining.len() % 3 != 0 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    if fundraiser.owner() != &crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
+
+    // Same conditions as a self-service refund
+    let current_time = Clock::get()?.unix_timestamp;
+    if !fundraiser_data.has_ended(current_time) {
+        return Err(FundraiserError::FundraiserNotEnded.into());
+    }
+
+    if fundraiser_data.target_met() {
+        return Err(FundraiserError::TargetMet.into());
+    }
+
+    if fundraiser_data.proposal_active != 0 {
+        return Err(FundraiserError::ProposalActive.into());
+    }
+
+    if fundraiser_data.finalize_approved != 0 {
+        return Err(FundraiserError::FinalizeApproved.into());
+    }
+
+    let is_sol = fundraiser_data.is_sol();
+    if is_sol {
+        fundraiser_data.check_sol_vault(fundraiser, vault)?;
+    } else if fundraiser_data.mint_to_raise != *mint_to_raise.key() {
+        // Fail before paying for any contributor ATA of the wrong mint
+        return Err(FundraiserError::InvalidMint.into());
+    }
+
+    let maker_key = fundraiser_data.maker;
+    let bump_bytes = [fundraiser_data.bump];
+    let campaign_id_bytes = fundraiser_data.campaign_id.to_le_bytes();
+    let fundraiser_seeds: [Seed; 4] = [
+        Seed::from(b"fundraiser"),
+        Seed::from(maker_key.as_ref()),
+        Seed::from(&campaign_id_bytes),
+        Seed::from(&bump_bytes),
+    ];
+
+    for entry in remaining.chunks_exact(3) {
+        let [wallet, contributor_account, contributor_ata] = entry else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if contributor_account.owner() != &crate::ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let (contributor_pda, _) = pubkey::find_program_address(
+            &[
+                b"contributor",
+                fundraiser.key().as_ref(),
+                wallet.key().as_ref(),
+                campaign_id_bytes.as_ref(),
+            ],
+            &crate::ID,
+        );
+        if contributor_pda != *contributor_account.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let refund_amount = Contributor::from_account_info(contributor_account)?.amount;
+
+        if refund_amount > 0 {
+            if is_sol {
+                let vault_lamports = vault.lamports()
+                    .checked_sub(refund_amount)
+                    .ok_or(ProgramError::InsufficientFunds)?;
+                *vault.try_borrow_mut_lamports()? = vault_lamports;
+                *wallet.try_borrow_mut_lamports()? += refund_amount;
+            } else {
+                // The ATA program rejects any account that isn't wallet's ATA for this mint
+                CreateIdempotent {
+                    funding_account: cranker,
+                    account: contributor_ata,
+                    wallet,
+                    mint: mint_to_raise,
+                    system_program,
+                    token_program,
+                }.invoke()?;
+
+                Transfer {
+                    from: vault,
+                    to: contributor_ata,
+                    authority: fundraiser,
+                    amount: refund_amount,
+                }.invoke_signed(&[Signer::from(&fundraiser_seeds)])?;
+            }
+
+            fundraiser_data.current_amount = fundraiser_data.current_amount.saturating_sub(refund_amount);
+        }
+
+        // Close contributor account, rent back to the contributor minus the bounty
+        {
+            let contributor_lamports = contributor_account.lamports();
+            let bounty = contributor_lamports * CRANK_BOUNTY_PERCENTAGE / PERCENTAGE_SCALER;
+
+            *cranker.try_borrow_mut_lamports()? += bounty;
+            *wallet.try_borrow_mut_lamports()? += contributor_lamports - bounty;
+            *contributor_account.try_borrow_mut_lamports()? = 0;
+        }
+
+        contributor_account.realloc(0, false)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use pinocchio::account_info::AccountInfo;
+use pinocchio::instruction::{Seed, Signer};
+use pinocchio::program_error::ProgramError;
+use pinocchio::{pubkey, ProgramResult};
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::rent::Rent;
+use pinocchio::sysvars::Sysvar;
+use pinocchio_system::instructions::CreateAccount;
+use crate::constants::{MAX_PROPOSALS, VOTING_PERIOD_SECONDS};
+use crate::error::FundraiserError;
+use crate::state::{Fundraiser, Proposal, ProposalKind};
+
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug)]
+pub struct CreateProposalIxData {
+    pub kind: u8,
+    pub extension_days: u8,
+}
+
+impl CreateProposalIxData {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    #[inline(always)]
+    pub unsafe fn load_ix_data(bytes: &[u8]) -> Result<Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(*(bytes.as_ptr() as *const Self))
+    }
+}
+
+pub fn process_create_proposal(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [
+        maker,
+        fundraiser,
+        proposal,
+        _system_program,
+        rent_sysvar,
+        ..
+    ] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let ix_data = unsafe { CreateProposalIxData::load_ix_data(data)? };
+    let kind = ProposalKind::try_from(ix_data.kind)?;
+
+    if !maker.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if fundraiser.owner() != &crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
+
+    if fundraiser_data.maker != *maker.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+
+    if kind == ProposalKind::Extend
+        && (ix_data.extension_days == 0 || fundraiser_data.duration.checked_add(ix_data.extension_days).is_none())
+    {
+        return Err(FundraiserError::InvalidProposal.into());
+    }
+
+    // Only a campaign that ended below target goes to a vote
+    let current_time = Clock::get()?.unix_timestamp;
+    if !fundraiser_data.has_ended(current_time) {
+        return Err(FundraiserError::FundraiserNotEnded.into());
+    }
+
+    if fundraiser_data.can_finalize() {
+        return Err(FundraiserError::TargetMet.into());
+    }
+
+    if fundraiser_data.current_amount == 0 {
+        return Err(FundraiserError::NoContribution.into());
+    }
+
+    if fundraiser_data.proposal_active != 0 {
+        return Err(FundraiserError::ProposalActive.into());
+    }
+
+    // Proposals freeze refunds, so the maker can't keep opening them back to back
+    if fundraiser_data.proposal_failed != 0 || fundraiser_data.proposal_count >= MAX_PROPOSALS {
+        return Err(FundraiserError::ProposalLimitReached.into());
+    }
+
+    // Each proposal gets a fresh PDA so vote records never collide across proposals
+    let proposal_index_bytes = fundraiser_data.proposal_count.to_le_bytes();
+    let seeds = &[b"proposal", fundraiser.key().as_ref(), proposal_index_bytes.as_ref()];
+    let (pda_proposal, bump) = pubkey::find_program_address(seeds, &crate::ID);
+
+    if pda_proposal != *proposal.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::from_account_info(rent_sysvar)?;
+
+    let bump_seed = [bump];
+    let proposal_seeds: [Seed; 4] = [
+        Seed::from(b"proposal"),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(&proposal_index_bytes),
+        Seed::from(&bump_seed),
+    ];
+
+    CreateAccount {
+        from: maker,
+        to: proposal,
+        lamports: rent.minimum_balance(Proposal::LEN),
+        space: Proposal::LEN as u64,
+        owner: &crate::ID,
+    }.invoke_signed(&[Signer::from(&proposal_seeds)])?;
+
+    let proposal_data = Proposal::from_account_info(proposal)?;
+    proposal_data.fundraiser = *fundraiser.key();
+    proposal_data.kind = kind as u8;
+    proposal_data.extension_days = ix_data.extension_days;
+    proposal_data.yes_votes = 0;
+    proposal_data.no_votes = 0;
+    proposal_data.total_weight = fundraiser_data.current_amount;
+    proposal_data.voting_ends_at = current_time + VOTING_PERIOD_SECONDS;
+    proposal_data.bump = bump;
+
+    fundraiser_data.proposal_active = 1;
+    fundraiser_data.proposal_count += 1;
+
+    Ok(())
+}
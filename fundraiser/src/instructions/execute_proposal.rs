@@ -0,0 +1,80 @@
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::ProgramResult;
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::Sysvar;
+use crate::error::FundraiserError;
+use crate::state::{Fundraiser, Proposal, ProposalKind};
+
+/// Permissionless: settles the open proposal once voting has ended (or the
+/// yes side can no longer lose) and returns the proposal rent to the maker.
+pub fn process_execute_proposal(accounts: &[AccountInfo]) -> ProgramResult {
+    let [
+        maker,
+        fundraiser,
+        proposal,
+        ..
+    ] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if fundraiser.owner() != &crate::ID || proposal.owner() != &crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
+    let proposal_data = Proposal::from_account_info(proposal)?;
+
+    if fundraiser_data.maker != *maker.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+
+    if proposal_data.fundraiser != *fundraiser.key() {
+        return Err(FundraiserError::InvalidProposal.into());
+    }
+
+    if fundraiser_data.proposal_active == 0 {
+        return Err(FundraiserError::NoActiveProposal.into());
+    }
+
+    let quorum = fundraiser_data.quorum;
+    let threshold = fundraiser_data.threshold;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let decided = proposal_data.decided(quorum, threshold);
+    if current_time < proposal_data.voting_ends_at && !decided {
+        return Err(FundraiserError::VotingNotEnded.into());
+    }
+
+    if proposal_data.passed(quorum, threshold) {
+        match ProposalKind::try_from(proposal_data.kind)? {
+            ProposalKind::Finalize => {
+                fundraiser_data.finalize_approved = 1;
+            }
+            // Counted from now if the vote ran past the campaign's end, so
+            // contributors get the days they voted for
+            ProposalKind::Extend => match fundraiser_data.extended_duration(proposal_data.extension_days, current_time) {
+                Some(duration) => fundraiser_data.duration = duration,
+                // Too long to track, settle it like a rejected proposal
+                None => fundraiser_data.proposal_failed = 1,
+            },
+        }
+    } else {
+        // Contributors said no, leave refunds open for good
+        fundraiser_data.proposal_failed = 1;
+    }
+
+    // Passed or not, the vote is over and refunds can resume
+    fundraiser_data.proposal_active = 0;
+
+    // Close proposal account and transfer lamports to maker
+    {
+        let proposal_lamports = proposal.lamports();
+        *maker.try_borrow_mut_lamports()? += proposal_lamports;
+        *proposal.try_borrow_mut_lamports()? = 0;
+    }
+
+    proposal.realloc(0, false)?;
+
+    Ok(())
+}
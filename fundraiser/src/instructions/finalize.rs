@@ -33,52 +33,68 @@ pub fn process_finalize(accounts: &[AccountInfo]) -> ProgramResult {
     let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
     let bump = fundraiser_data.bump;
 
-    // Check target met or not
-    if fundraiser_data.current_amount < fundraiser_data.amount_to_raise {
+    // SOL campaigns release funds without a PDA-signed CPI, so check the maker explicitly
+    if fundraiser_data.maker != *maker.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+
+    // Check target met, or a contributor vote approved finalizing anyway
+    if !fundraiser_data.can_finalize() {
         return Err(FundraiserError::TargetNotMet.into());
     }
     
     let bump_bytes = [bump];
-    let seeds: [Seed; 3] = [
+    let campaign_id_bytes = fundraiser_data.campaign_id.to_le_bytes();
+    let seeds: [Seed; 4] = [
         Seed::from(b"fundraiser"),
         Seed::from(maker.key().as_ref()),
+        Seed::from(&campaign_id_bytes),
         Seed::from(&bump_bytes),
     ];
     let fundraiser_signer = Signer::from(&seeds);
 
   
-    if maker_ata.data_is_empty() {
-        CreateIdempotent {
-            funding_account: maker,
-            account: maker_ata,
-            wallet: maker,
-            mint: mint_to_raise,
-            system_program,
-            token_program,
-        }.invoke()?; 
+    if fundraiser_data.is_sol() {
+        // Drain the SOL vault, contributions plus its rent-exempt reserve, to the maker
+        fundraiser_data.check_sol_vault(fundraiser, vault)?;
+
+        let vault_lamports = vault.lamports();
+        *maker.try_borrow_mut_lamports()? += vault_lamports;
+        *vault.try_borrow_mut_lamports()? = 0;
     } else {
-        // Verify it's the correct ATA
-        if maker_ata.owner() != &PINOCCHIO_TOKEN_ID {
-            return Err(ProgramError::IllegalOwner);
+        if maker_ata.data_is_empty() {
+            CreateIdempotent {
+                funding_account: maker,
+                account: maker_ata,
+                wallet: maker,
+                mint: mint_to_raise,
+                system_program,
+                token_program,
+            }.invoke()?;
+        } else {
+            // Verify it's the correct ATA
+            if maker_ata.owner() != &PINOCCHIO_TOKEN_ID {
+                return Err(ProgramError::IllegalOwner);
+            }
         }
-    }
 
-    // Transfer all tokens from vault to maker_ata
-    let amount_to_transfer = fundraiser_data.current_amount;
-
-    Transfer {
-        from: vault,
-        to: maker_ata,
-        authority: fundraiser,
-        amount: amount_to_transfer,
-    }.invoke_signed(&[fundraiser_signer.clone()])?;
-
-    // Close vault to reclaim rent
-    CloseAccount {
-        account: vault,
-        destination: maker,
-        authority: fundraiser,
-    }.invoke_signed(&[fundraiser_signer])?;
+        // Transfer all tokens from vault to maker_ata
+        let amount_to_transfer = fundraiser_data.current_amount;
+
+        Transfer {
+            from: vault,
+            to: maker_ata,
+            authority: fundraiser,
+            amount: amount_to_transfer,
+        }.invoke_signed(&[fundraiser_signer.clone()])?;
+
+        // Close vault to reclaim rent
+        CloseAccount {
+            account: vault,
+            destination: maker,
+            authority: fundraiser,
+        }.invoke_signed(&[fundraiser_signer])?;
+    }
 
     // Close fundraiser account and transfer lamports to maker
     {
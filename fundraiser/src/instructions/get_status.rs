@@ -0,0 +1,31 @@
+use pinocchio::account_info::AccountInfo;
+use pinocchio::cpi::set_return_data;
+use pinocchio::program_error::ProgramError;
+use pinocchio::ProgramResult;
+use pinocchio::sysvars::clock::Clock;
+use pinocchio::sysvars::Sysvar;
+
+use crate::state::{Fundraiser, StatusReport};
+
+pub fn process_get_status(accounts: &[AccountInfo]) -> ProgramResult {
+    let [fundraiser, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Finalize closes the fundraiser account, so an empty account means it's done
+    let report = if fundraiser.data_is_empty() {
+        StatusReport::FINALIZED
+    } else {
+        if fundraiser.owner() != &crate::ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
+        let current_time = Clock::get()?.unix_timestamp;
+        fundraiser_data.status(current_time)
+    };
+
+    set_return_data(&report.to_bytes());
+
+    Ok(())
+}
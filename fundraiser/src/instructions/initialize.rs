@@ -1,20 +1,26 @@
 use pinocchio::account_info::AccountInfo;
 use pinocchio::instruction::{Seed, Signer};
 use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
 use pinocchio::{pubkey, ProgramResult};
 use pinocchio::sysvars::clock::Clock;
 use pinocchio::sysvars::rent::Rent;
 use pinocchio::sysvars::Sysvar;
 use pinocchio_associated_token_account::instructions::CreateIdempotent;
 use pinocchio_system::instructions::CreateAccount;
-use crate::constants::MIN_AMOUNT_TO_RAISE;
-use crate::state::Fundraiser;
+use crate::constants::{MIN_AMOUNT_TO_RAISE, PERCENTAGE_SCALER};
+use crate::error::FundraiserError;
+use crate::state::{Fundraiser, FundraiserMode};
 
 #[repr(C, packed)]
 #[derive(Clone, Copy, Debug)]
 pub struct InitializeIxData {
     pub amount: u64,
     pub duration: u8,
+    pub campaign_id: u64,
+    pub mode: u8,
+    pub quorum: u8,
+    pub threshold: u8,
 }
 
 impl InitializeIxData {
@@ -58,6 +64,16 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         return Err(ProgramError::InvalidInstructionData);
     }
 
+    let mode = FundraiserMode::try_from(ix_data.mode)?;
+
+    if ix_data.quorum == 0
+        || ix_data.quorum as u64 > PERCENTAGE_SCALER
+        || ix_data.threshold == 0
+        || ix_data.threshold as u64 > PERCENTAGE_SCALER
+    {
+        return Err(FundraiserError::InvalidGovernanceConfig.into());
+    }
+
     // Load rent from the passed account
     let rent = Rent::from_account_info(rent_account)?;
 
@@ -65,8 +81,9 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
     let clock = Clock::get()?;
     let time_started = clock.unix_timestamp;
 
-    // Derive fundraiser PDA
-    let seeds = &[b"fundraiser", maker.key().as_ref()];
+    // Derive fundraiser PDA, one per (maker, campaign_id)
+    let campaign_id_bytes = ix_data.campaign_id.to_le_bytes();
+    let seeds = &[b"fundraiser", maker.key().as_ref(), campaign_id_bytes.as_ref()];
     let (pda_fundraiser, bump) = pubkey::find_program_address(seeds, &crate::ID);
 
     if pda_fundraiser != *fundraiser.key() {
@@ -74,9 +91,10 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
     }
 
     let bump_seed = [bump];
-    let fundraiser_seeds: [Seed; 3] = [
+    let fundraiser_seeds: [Seed; 4] = [
         Seed::from(b"fundraiser"),
         Seed::from(maker.key().as_ref()),
+        Seed::from(&campaign_id_bytes),
         Seed::from(&bump_seed),
     ];
     let fundraiser_signer = Signer::from(&fundraiser_seeds);
@@ -90,25 +108,63 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         owner: &crate::ID,
     }.invoke_signed(&[fundraiser_signer.clone()])?;
 
-    CreateIdempotent {
-        funding_account: maker,
-        account: vault,
-        wallet: fundraiser,
-        mint: mint_to_raise,
-        system_program: system_account,
-        token_program,
-    }.invoke_signed(&[fundraiser_signer])?;
+    let (mint_key, vault_bump) = match mode {
+        FundraiserMode::Token => {
+            CreateIdempotent {
+                funding_account: maker,
+                account: vault,
+                wallet: fundraiser,
+                mint: mint_to_raise,
+                system_program: system_account,
+                token_program,
+            }.invoke_signed(&[fundraiser_signer])?;
+
+            (*mint_to_raise.key(), 0)
+        }
+        FundraiserMode::Sol => {
+            // Lamports are held in a program-owned PDA with no data, funded by the
+            // maker up to rent exemption so contributions never drop below it
+            let (pda_vault, vault_bump) =
+                pubkey::find_program_address(&[b"vault", fundraiser.key().as_ref()], &crate::ID);
+
+            if pda_vault != *vault.key() {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            let vault_bump_seed = [vault_bump];
+            let vault_seeds: [Seed; 3] = [
+                Seed::from(b"vault"),
+                Seed::from(fundraiser.key().as_ref()),
+                Seed::from(&vault_bump_seed),
+            ];
+
+            CreateAccount {
+                from: maker,
+                to: vault,
+                lamports: rent.minimum_balance(0),
+                space: 0,
+                owner: &crate::ID,
+            }.invoke_signed(&[Signer::from(&vault_seeds)])?;
+
+            (Pubkey::default(), vault_bump)
+        }
+    };
 
     // Initialize the fundraiser state
     let fundraiser_data = Fundraiser::from_account_info(fundraiser)?;
     fundraiser_data.new(
         maker.key(),
-        mint_to_raise.key(),
+        &mint_key,
         ix_data.amount,
         time_started,
         ix_data.duration,
         bump,
     );
+    fundraiser_data.campaign_id = ix_data.campaign_id;
+    fundraiser_data.mode = mode as u8;
+    fundraiser_data.vault_bump = vault_bump;
+    fundraiser_data.quorum = ix_data.quorum;
+    fundraiser_data.threshold = ix_data.threshold;
 
     Ok(())
 }
@@ -1,7 +1,7 @@
 
 use pinocchio::program_error::ProgramError;
 use crate::FundraiserInstructions::Finalize;
-use crate::instructions::FundraiserInstructions::{Contribute, Initialize, Refund};
+use crate::instructions::FundraiserInstructions::{CastVote, Contribute, CrankRefund, CreateProposal, ExecuteProposal, GetStatus, Initialize, Refund};
 mod initialize;
 pub use initialize::*;
 mod contribute;
@@ -12,11 +12,29 @@ pub use finalize::*;
 mod refund;
 pub use refund::*;
 
+mod get_status;
+pub use get_status::*;
+
+mod create_proposal;
+pub use create_proposal::*;
+mod cast_vote;
+pub use cast_vote::*;
+mod execute_proposal;
+pub use execute_proposal::*;
+
+mod crank_refund;
+pub use crank_refund::*;
+
 pub enum FundraiserInstructions {
     Initialize = 0,
     Contribute=1,
     Refund=2,
     Finalize=3,
+    GetStatus=4,
+    CreateProposal=5,
+    CastVote=6,
+    ExecuteProposal=7,
+    CrankRefund=8,
 }
 
 impl TryFrom<&u8> for FundraiserInstructions {
@@ -28,6 +46,11 @@ impl TryFrom<&u8> for FundraiserInstructions {
             1 => Ok(Contribute),
             2 => Ok(Refund),
             3 => Ok(Finalize),
+            4 => Ok(GetStatus),
+            5 => Ok(CreateProposal),
+            6 => Ok(CastVote),
+            7 => Ok(ExecuteProposal),
+            8 => Ok(CrankRefund),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
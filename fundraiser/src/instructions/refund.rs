@@ -8,7 +8,6 @@ use pinocchio::{
     sysvars::Sysvar,
 };
 use pinocchio_token::instructions::Transfer;
-use crate::constants::SECONDS_TO_DAYS;
 use crate::error::FundraiserError;
 use crate::state::{Fundraiser, Contributor};
 
@@ -36,8 +35,7 @@ pub fn process_refund(accounts: &[AccountInfo]) -> ProgramResult {
 
     // Check fundraiser ended
     let current_time = Clock::get()?.unix_timestamp;
-    let elapsed_days = ((current_time - fundraiser_data.time_started) / SECONDS_TO_DAYS) as u8;
-    if elapsed_days <= fundraiser_data.duration {
+    if !fundraiser_data.has_ended(current_time) {
         return Err(FundraiserError::FundraiserNotEnded.into());
     }
 
@@ -46,28 +44,65 @@ pub fn process_refund(accounts: &[AccountInfo]) -> ProgramResult {
         return Err(FundraiserError::TargetMet.into());
     }
 
+    // Amounts are frozen while contributors vote, and an approved finalize wins
+    if fundraiser_data.proposal_active != 0 {
+        return Err(FundraiserError::ProposalActive.into());
+    }
+
+    if fundraiser_data.finalize_approved != 0 {
+        return Err(FundraiserError::FinalizeApproved.into());
+    }
+
     if contributor_data.amount == 0 {
         return Err(FundraiserError::NoContribution.into());
     }
 
+    // The contributor record must belong to this campaign and this contributor
+    let campaign_id_bytes = fundraiser_data.campaign_id.to_le_bytes();
+    let (contributor_pda, _) = pubkey::find_program_address(
+        &[
+            b"contributor",
+            fundraiser.key().as_ref(),
+            contributor.key().as_ref(),
+            campaign_id_bytes.as_ref(),
+        ],
+        &crate::ID,
+    );
+    if contributor_pda != *contributor_account.key() {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
     let refund_amount = contributor_data.amount;
 
     // ✅ Use the bump stored in fundraiser_data
     let bump_bytes = [fundraiser_data.bump];
-    let fundraiser_seeds: [Seed; 3] = [
+    let fundraiser_seeds: [Seed; 4] = [
         Seed::from(b"fundraiser"),
         Seed::from(maker.key().as_ref()),
+        Seed::from(&campaign_id_bytes),
         Seed::from(&bump_bytes),
     ];
     let fundraiser_signer = Signer::from(&fundraiser_seeds);
 
-    // Transfer tokens from vault → contributor ATA
-    Transfer {
-        from: vault,
-        to: contributor_ata,
-        authority: fundraiser,
-        amount: refund_amount,
-    }.invoke_signed(&[fundraiser_signer])?;
+    if fundraiser_data.is_sol() {
+        // The vault is program-owned, so lamports move directly. Only contributed
+        // lamports leave here; the maker's rent-exempt reserve stays until finalize.
+        fundraiser_data.check_sol_vault(fundraiser, vault)?;
+
+        let vault_lamports = vault.lamports()
+            .checked_sub(refund_amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        *vault.try_borrow_mut_lamports()? = vault_lamports;
+        *contributor.try_borrow_mut_lamports()? += refund_amount;
+    } else {
+        // Transfer tokens from vault → contributor ATA
+        Transfer {
+            from: vault,
+            to: contributor_ata,
+            authority: fundraiser,
+            amount: refund_amount,
+        }.invoke_signed(&[fundraiser_signer])?;
+    }
 
     // Update state
     fundraiser_data.current_amount = fundraiser_data.current_amount.saturating_sub(refund_amount);
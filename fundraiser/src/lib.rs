@@ -6,6 +6,7 @@ pub use instructions::*;
 pub mod state;
 pub mod constants;
 pub mod error;
+pub mod client;
 pub mod tests;
 
 
@@ -43,6 +44,26 @@ pub fn process_instruction(
         FundraiserInstructions::Finalize =>{
             process_finalize(accounts)?
         }
+
+        FundraiserInstructions::GetStatus => {
+            process_get_status(accounts)?
+        }
+
+        FundraiserInstructions::CreateProposal => {
+            process_create_proposal(accounts, rest_data)?
+        }
+
+        FundraiserInstructions::CastVote => {
+            process_cast_vote(accounts, rest_data)?
+        }
+
+        FundraiserInstructions::ExecuteProposal => {
+            process_execute_proposal(accounts)?
+        }
+
+        FundraiserInstructions::CrankRefund => {
+            process_crank_refund(accounts)?
+        }
     }
 
     Ok(())
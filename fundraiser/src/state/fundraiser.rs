@@ -1,7 +1,27 @@
 use pinocchio::account_info::AccountInfo;
 use pinocchio::program_error::ProgramError;
-use pinocchio::pubkey::Pubkey;
+use pinocchio::pubkey::{create_program_address, Pubkey};
 
+/// What a campaign raises: an SPL token held in the vault ATA, or native SOL
+/// held as lamports in a program-owned vault PDA.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundraiserMode {
+    Token = 0,
+    Sol = 1,
+}
+
+impl TryFrom<u8> for FundraiserMode {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundraiserMode::Token),
+            1 => Ok(FundraiserMode::Sol),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
 
 #[repr(C,packed)]
 #[derive(Debug, Clone, Copy)]
@@ -13,6 +33,15 @@ pub struct Fundraiser {
     pub time_started: i64,      // 8 bytes
     pub duration: u8,           // 1 byte
     pub bump: u8,               // 1 byte
+    pub campaign_id: u64,       // 8 bytes
+    pub mode: u8,               // 1 byte
+    pub vault_bump: u8,         // 1 byte, SOL vault PDA only
+    pub quorum: u8,             // 1 byte, % of raised amount that must vote
+    pub threshold: u8,          // 1 byte, % of cast votes that must approve
+    pub proposal_active: u8,    // 1 byte
+    pub finalize_approved: u8,  // 1 byte
+    pub proposal_count: u64,    // 8 bytes
+    pub proposal_failed: u8,    // 1 byte, a vote was lost and refunds stay open
 }
 
 impl Fundraiser {
@@ -52,5 +81,42 @@ impl Fundraiser {
 
         self.duration = duration;
         self.bump = bump;
+
+        self.campaign_id = 0;
+        self.mode = FundraiserMode::Token as u8;
+        self.vault_bump = 0;
+        self.quorum = 0;
+        self.threshold = 0;
+        self.proposal_active = 0;
+        self.finalize_approved = 0;
+        self.proposal_count = 0;
+        self.proposal_failed = 0;
+    }
+
+    pub fn is_sol(&self) -> bool {
+        self.mode == FundraiserMode::Sol as u8
+    }
+
+    pub fn target_met(&self) -> bool {
+        self.current_amount >= self.amount_to_raise
+    }
+
+    /// The maker may take the funds: either the target was met or contributors voted to allow it.
+    pub fn can_finalize(&self) -> bool {
+        self.target_met() || self.finalize_approved != 0
+    }
+
+    /// Checks `vault` is the SOL vault PDA (`[b"vault", fundraiser]`) for this campaign.
+    pub fn check_sol_vault(&self, fundraiser: &AccountInfo, vault: &AccountInfo) -> Result<(), ProgramError> {
+        let vault_bump = [self.vault_bump];
+        let expected = create_program_address(
+            &[b"vault", fundraiser.key().as_ref(), &vault_bump],
+            &crate::ID,
+        )?;
+
+        if expected != *vault.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(())
     }
 }
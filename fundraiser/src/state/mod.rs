@@ -1,5 +1,9 @@
 pub mod fundraiser;
 pub mod contributor;
+pub mod status;
+pub mod proposal;
 
 pub use fundraiser::*;
-pub use contributor::*;
\ No newline at end of file
+pub use contributor::*;
+pub use status::*;
+pub use proposal::*;
@@ -0,0 +1,102 @@
+use pinocchio::account_info::AccountInfo;
+use pinocchio::program_error::ProgramError;
+use pinocchio::pubkey::Pubkey;
+
+use crate::constants::PERCENTAGE_SCALER;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalKind {
+    /// Let the maker finalize even though the target was missed
+    Finalize = 0,
+    /// Add `extension_days` to the campaign duration, counted from its original start
+    Extend = 1,
+}
+
+impl TryFrom<u8> for ProposalKind {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ProposalKind::Finalize),
+            1 => Ok(ProposalKind::Extend),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[repr(C,packed)]
+#[derive(Debug, Clone, Copy)]
+pub struct Proposal {
+    pub fundraiser: Pubkey,     // 32 bytes
+    pub kind: u8,               // 1 byte
+    pub extension_days: u8,     // 1 byte
+    pub yes_votes: u64,         // 8 bytes
+    pub no_votes: u64,          // 8 bytes
+    pub total_weight: u64,      // 8 bytes, raised amount when the proposal opened
+    pub voting_ends_at: i64,    // 8 bytes
+    pub bump: u8,               // 1 byte
+}
+
+impl Proposal {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    #[inline(always)]
+    pub unsafe fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self)
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { Self::from_account_info_unchecked(account_info) })
+    }
+
+    /// Quorum is measured against the raised amount, threshold against the votes cast.
+    pub fn passed(&self, quorum: u8, threshold: u8) -> bool {
+        let yes = self.yes_votes as u128;
+        let cast = yes + self.no_votes as u128;
+        let total = self.total_weight as u128;
+        let scaler = PERCENTAGE_SCALER as u128;
+
+        cast > 0
+            && cast * scaler >= total * quorum as u128
+            && yes * scaler >= cast * threshold as u128
+    }
+
+    /// Yes votes alone meet quorum and threshold even if every remaining
+    /// contributor voted no, so the outcome can no longer change.
+    pub fn decided(&self, quorum: u8, threshold: u8) -> bool {
+        let yes = self.yes_votes as u128;
+        let total = self.total_weight as u128;
+        let scaler = PERCENTAGE_SCALER as u128;
+
+        yes > 0
+            && yes * scaler >= total * quorum as u128
+            && yes * scaler >= total * threshold as u128
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct VoteRecord {
+    pub weight: u64,
+    pub approve: u8,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    #[inline(always)]
+    pub unsafe fn from_account_info_unchecked(account_info: &AccountInfo) -> &mut Self {
+        &mut *(account_info.borrow_mut_data_unchecked().as_ptr() as *mut Self)
+    }
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        if account_info.data_len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { Self::from_account_info_unchecked(account_info) })
+    }
+}
@@ -0,0 +1,118 @@
+use crate::constants::{PERCENTAGE_SCALER, SECONDS_TO_DAYS};
+use crate::state::Fundraiser;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundraiserStatus {
+    Active = 0,
+    Succeeded = 1,
+    Failed = 2,
+    Finalized = 3,
+}
+
+impl TryFrom<u8> for FundraiserStatus {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundraiserStatus::Active),
+            1 => Ok(FundraiserStatus::Succeeded),
+            2 => Ok(FundraiserStatus::Failed),
+            3 => Ok(FundraiserStatus::Finalized),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Lifecycle snapshot returned by `GetStatus` through `set_return_data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusReport {
+    pub status: FundraiserStatus,
+    pub time_remaining: i64,  // seconds until contributions close, 0 once ended
+    pub percent_funded: u64,  // current_amount * 100 / amount_to_raise, may exceed 100
+}
+
+impl StatusReport {
+    pub const LEN: usize = 1 + 8 + 8;
+
+    pub const FINALIZED: Self = Self {
+        status: FundraiserStatus::Finalized,
+        time_remaining: 0,
+        percent_funded: 0,
+    };
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0] = self.status as u8;
+        bytes[1..9].copy_from_slice(&self.time_remaining.to_le_bytes());
+        bytes[9..17].copy_from_slice(&self.percent_funded.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::LEN {
+            return None;
+        }
+        Some(Self {
+            status: FundraiserStatus::try_from(bytes[0]).ok()?,
+            time_remaining: i64::from_le_bytes(bytes[1..9].try_into().ok()?),
+            percent_funded: u64::from_le_bytes(bytes[9..17].try_into().ok()?),
+        })
+    }
+}
+
+impl Fundraiser {
+    /// Contributions are accepted while the elapsed whole days are `<= duration`,
+    /// so the campaign closes at the start of day `duration + 1`.
+    pub fn end_time(&self) -> i64 {
+        self.time_started
+            .saturating_add((self.duration as i64 + 1) * SECONDS_TO_DAYS)
+    }
+
+    pub fn has_ended(&self, current_time: i64) -> bool {
+        current_time >= self.end_time()
+    }
+
+    /// Duration keeping the campaign open for `extension_days` past the later
+    /// of its end and `current_time`, rounded up to whole days. `None` once
+    /// that no longer fits in a `u8`.
+    pub fn extended_duration(&self, extension_days: u8, current_time: i64) -> Option<u8> {
+        let open_for = self.end_time().max(current_time) - self.time_started;
+        let days = (open_for + SECONDS_TO_DAYS - 1) / SECONDS_TO_DAYS + extension_days as i64 - 1;
+        u8::try_from(days).ok()
+    }
+
+    pub fn status(&self, current_time: i64) -> StatusReport {
+        let current_amount = self.current_amount;
+        let amount_to_raise = self.amount_to_raise;
+
+        let has_ended = self.has_ended(current_time);
+
+        // The outcome is only settled once contributions close
+        let status = if !has_ended {
+            FundraiserStatus::Active
+        } else if self.can_finalize() {
+            FundraiserStatus::Succeeded
+        } else {
+            FundraiserStatus::Failed
+        };
+
+        let time_remaining = if has_ended {
+            0
+        } else {
+            self.end_time() - current_time
+        };
+
+        let percent_funded = if amount_to_raise == 0 {
+            0
+        } else {
+            (current_amount as u128 * PERCENTAGE_SCALER as u128 / amount_to_raise as u128) as u64
+        };
+
+        StatusReport {
+            status,
+            time_remaining,
+            percent_funded,
+        }
+    }
+}
@@ -14,10 +14,12 @@ mod tests {
     use solana_pubkey::Pubkey;
     use solana_signer::Signer;
     use solana_transaction::Transaction;
-    use crate::constants::MIN_AMOUNT_TO_RAISE;
-    use crate::state::Fundraiser;
-
+    use crate::client::{decode_status_return_data, fundraiser_status, get_status_ix_data};
+    use crate::constants::{CRANK_BOUNTY_PERCENTAGE, MAX_PROPOSALS, MIN_AMOUNT_TO_RAISE, VOTING_PERIOD_SECONDS};
+    use crate::state::{Fundraiser, FundraiserMode, FundraiserStatus, ProposalKind, StatusReport};
 
+    const QUORUM: u8 = 50;
+    const THRESHOLD: u8 = 60;
 
     fn program_id() -> Pubkey {
         Pubkey::from(crate::ID)
@@ -53,10 +55,405 @@ mod tests {
 
         (svm, payer)
     }
+
+    fn get_status(svm: &mut LiteSVM, payer: &Keypair, fundraiser_pda: &Pubkey) -> StatusReport {
+        let get_status_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![AccountMeta::new_readonly(*fundraiser_pda, false)],
+            data: get_status_ix_data().to_vec(),
+        };
+
+        // Repeated identical queries would otherwise be rejected as already processed
+        svm.expire_blockhash();
+        let tx = Transaction::new(
+            &[payer],
+            Message::new(&[get_status_ix], Some(&payer.pubkey())),
+            svm.latest_blockhash()
+        );
+        let result = svm.send_transaction(tx).expect("GetStatus failed");
+        msg!("GetStatus CUs: {}", result.compute_units_consumed);
+
+        let report = decode_status_return_data(&result.return_data.data)
+            .expect("GetStatus should return a status report");
+
+        // The off-chain helper must agree with the on-chain instruction
+        let now = svm.get_sysvar::<Clock>().unix_timestamp;
+        let account = svm.get_account(fundraiser_pda);
+        let off_chain = fundraiser_status(account.as_ref().map(|a| a.data.as_slice()), now).unwrap();
+        assert_eq!(report, off_chain, "On-chain and off-chain status differ");
+
+        report
+    }
+
+    fn initialize_campaign(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        mint_to_raise: &Pubkey,
+        campaign_id: u64,
+        amount_to_raise: u64,
+        duration: u8,
+    ) -> (Pubkey, Pubkey) {
+        let (fundraiser_pda, _bump) = Pubkey::find_program_address(
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id(),
+        );
+
+        let vault_pda = CreateAssociatedTokenAccount::new(svm, payer, mint_to_raise)
+            .owner(&fundraiser_pda)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let init_data = [
+            vec![0u8],
+            amount_to_raise.to_le_bytes().to_vec(),
+            vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
+        ].concat();
+
+        let init_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(*mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: init_data,
+        };
+
+        let tx = Transaction::new(&[payer], Message::new(&[init_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).expect("Initialize failed");
+
+        (fundraiser_pda, vault_pda)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn contribute(
+        svm: &mut LiteSVM,
+        contributor: &Keypair,
+        contributor_ata: &Pubkey,
+        mint_to_raise: &Pubkey,
+        fundraiser_pda: &Pubkey,
+        vault_pda: &Pubkey,
+        campaign_id: u64,
+        amount: u64,
+    ) -> Pubkey {
+        let (contributor_pda, _) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id(),
+        );
+
+        let contribute_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(*mint_to_raise, false),
+                AccountMeta::new(*fundraiser_pda, false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new(*contributor_ata, false),
+                AccountMeta::new(*vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: [vec![1u8], amount.to_le_bytes().to_vec()].concat(),
+        };
+
+        let tx = Transaction::new(
+            &[contributor],
+            Message::new(&[contribute_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).expect("Contribute failed");
+
+        contributor_pda
+    }
+
+    fn initialize_sol_campaign(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        campaign_id: u64,
+        amount_to_raise: u64,
+        duration: u8,
+    ) -> (Pubkey, Pubkey) {
+        let (fundraiser_pda, _bump) = Pubkey::find_program_address(
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id(),
+        );
+        let (vault_pda, _vault_bump) = Pubkey::find_program_address(
+            &[b"vault", fundraiser_pda.as_ref()],
+            &program_id(),
+        );
+
+        let init_data = [
+            vec![0u8],
+            amount_to_raise.to_le_bytes().to_vec(),
+            vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Sol as u8],
+            vec![QUORUM, THRESHOLD],
+        ].concat();
+
+        // SOL campaigns have no mint, the system program fills the slot
+        let init_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: init_data,
+        };
+
+        let tx = Transaction::new(&[payer], Message::new(&[init_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).expect("Initialize (SOL) failed");
+
+        (fundraiser_pda, vault_pda)
+    }
+
+    fn contribute_sol(
+        svm: &mut LiteSVM,
+        contributor: &Keypair,
+        fundraiser_pda: &Pubkey,
+        vault_pda: &Pubkey,
+        campaign_id: u64,
+        amount: u64,
+    ) -> Pubkey {
+        let (contributor_pda, _) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id(),
+        );
+
+        // Mint and contributor ATA slots are unused for SOL campaigns
+        let contribute_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(*fundraiser_pda, false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(*vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: [vec![1u8], amount.to_le_bytes().to_vec()].concat(),
+        };
+
+        let tx = Transaction::new(
+            &[contributor],
+            Message::new(&[contribute_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).expect("Contribute (SOL) failed");
+
+        contributor_pda
+    }
+
+    fn create_proposal(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        fundraiser_pda: &Pubkey,
+        proposal_index: u64,
+        kind: ProposalKind,
+        extension_days: u8,
+    ) -> Pubkey {
+        let (proposal_pda, created) = try_create_proposal(svm, payer, fundraiser_pda, proposal_index, kind, extension_days);
+        assert!(created, "CreateProposal failed");
+        proposal_pda
+    }
+
+    fn try_create_proposal(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        fundraiser_pda: &Pubkey,
+        proposal_index: u64,
+        kind: ProposalKind,
+        extension_days: u8,
+    ) -> (Pubkey, bool) {
+        let (proposal_pda, _) = Pubkey::find_program_address(
+            &[b"proposal", fundraiser_pda.as_ref(), &proposal_index.to_le_bytes()],
+            &program_id(),
+        );
+
+        let create_proposal_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(*fundraiser_pda, false),
+                AccountMeta::new(proposal_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: vec![5u8, kind as u8, extension_days],
+        };
+
+        svm.expire_blockhash();
+        let tx = Transaction::new(
+            &[payer],
+            Message::new(&[create_proposal_ix], Some(&payer.pubkey())),
+            svm.latest_blockhash()
+        );
+        let created = svm.send_transaction(tx).is_ok();
+
+        (proposal_pda, created)
+    }
+
+    fn cast_vote(
+        svm: &mut LiteSVM,
+        contributor: &Keypair,
+        fundraiser_pda: &Pubkey,
+        contributor_pda: &Pubkey,
+        proposal_pda: &Pubkey,
+        approve: bool,
+    ) -> bool {
+        let (vote_pda, _) = Pubkey::find_program_address(
+            &[b"vote", proposal_pda.as_ref(), contributor.pubkey().as_ref()],
+            &program_id(),
+        );
+
+        let cast_vote_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(*fundraiser_pda, false),
+                AccountMeta::new_readonly(*contributor_pda, false),
+                AccountMeta::new(*proposal_pda, false),
+                AccountMeta::new(vote_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: vec![6u8, approve as u8],
+        };
+
+        svm.expire_blockhash();
+        let tx = Transaction::new(
+            &[contributor],
+            Message::new(&[cast_vote_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).is_ok()
+    }
+
+    fn execute_proposal(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        maker: &Pubkey,
+        fundraiser_pda: &Pubkey,
+        proposal_pda: &Pubkey,
+    ) -> bool {
+        let execute_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*maker, false),
+                AccountMeta::new(*fundraiser_pda, false),
+                AccountMeta::new(*proposal_pda, false),
+            ],
+            data: vec![7u8],
+        };
+
+        svm.expire_blockhash();
+        let tx = Transaction::new(
+            &[payer],
+            Message::new(&[execute_ix], Some(&payer.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).is_ok()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn refund(
+        svm: &mut LiteSVM,
+        contributor: &Keypair,
+        maker: &Pubkey,
+        mint_to_raise: &Pubkey,
+        fundraiser_pda: &Pubkey,
+        contributor_pda: &Pubkey,
+        contributor_ata: &Pubkey,
+        vault_pda: &Pubkey,
+    ) -> bool {
+        let refund_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(*maker, false),
+                AccountMeta::new_readonly(*mint_to_raise, false),
+                AccountMeta::new(*fundraiser_pda, false),
+                AccountMeta::new(*contributor_pda, false),
+                AccountMeta::new(*contributor_ata, false),
+                AccountMeta::new(*vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data: vec![2u8],
+        };
+
+        svm.expire_blockhash();
+        let tx = Transaction::new(
+            &[contributor],
+            Message::new(&[refund_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).is_ok()
+    }
+
+    /// Token campaign (duration 1 day) backed by two contributors, then moved past its end
+    /// with the target missed. Returns the contributors with their ATAs and contributor PDAs.
+    fn failed_campaign_with_two_contributors(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        mint_to_raise: &Pubkey,
+        amount_a: u64,
+        amount_b: u64,
+    ) -> (Pubkey, Pubkey, [(Keypair, Pubkey, Pubkey); 2]) {
+        let amount_to_raise = MIN_AMOUNT_TO_RAISE + 1_000_000;
+        let (fundraiser_pda, vault_pda) = initialize_campaign(svm, payer, mint_to_raise, 0, amount_to_raise, 1);
+
+        let mut backers = Vec::new();
+        for amount in [amount_a, amount_b] {
+            let contributor = Keypair::new();
+            svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+            let contributor_ata = CreateAssociatedTokenAccount::new(svm, payer, mint_to_raise)
+                .owner(&contributor.pubkey())
+                .token_program_id(&spl_token::ID)
+                .send()
+                .unwrap();
+
+            MintTo::new(svm, payer, mint_to_raise, &contributor_ata, amount)
+                .token_program_id(&spl_token::ID)
+                .send()
+                .unwrap();
+
+            let contributor_pda = contribute(svm, &contributor, &contributor_ata, mint_to_raise, &fundraiser_pda, &vault_pda, 0, amount);
+            backers.push((contributor, contributor_ata, contributor_pda));
+        }
+
+        let mut clock = Clock::default();
+        clock.unix_timestamp = 1700000000 + (86400 * 2);
+        svm.set_sysvar(&clock);
+
+        let backers: [(Keypair, Pubkey, Pubkey); 2] = backers.try_into().ok().unwrap();
+        (fundraiser_pda, vault_pda, backers)
+    }
+
     #[test]
     fn test_initialize() {
         let (mut svm, payer) = setup();
         let program_id = program_id();
+        let campaign_id: u64 = 0;
 
         msg!("Program ID: {}", program_id);
 
@@ -71,7 +468,7 @@ mod tests {
 
         // Derive the fundraiser PDA
         let (fundraiser_pda, bump) = Pubkey::find_program_address(
-            &[b"fundraiser", payer.pubkey().as_ref()],
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
         msg!("Fundraiser PDA: {}, bump: {}", fundraiser_pda, bump);
@@ -100,6 +497,9 @@ mod tests {
             vec![0u8],
             amount_to_raise.to_le_bytes().to_vec(),
             vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
         ].concat();
 
         msg!("Instruction data:");
@@ -152,6 +552,7 @@ mod tests {
         let time_started_bytes = &data[80..88];
         let duration_byte = data[88];
         let bump_byte = data[89];
+        let campaign_id_bytes = &data[90..98];
 
         // Verify state
         assert_eq!(maker_bytes, payer.pubkey().as_ref(), "Maker mismatch");
@@ -169,6 +570,12 @@ mod tests {
         assert_eq!(duration_byte, duration, "Duration mismatch");
         assert_eq!(bump_byte, bump, "Bump mismatch");
 
+        let stored_campaign_id = u64::from_le_bytes(campaign_id_bytes.try_into().unwrap());
+        assert_eq!(stored_campaign_id, campaign_id, "Campaign id mismatch");
+        assert_eq!(data[98], FundraiserMode::Token as u8, "Mode mismatch");
+        assert_eq!(data[100], QUORUM, "Quorum mismatch");
+        assert_eq!(data[101], THRESHOLD, "Threshold mismatch");
+
         msg!("Maker: {}", payer.pubkey());
         msg!("Mint: {}", mint_to_raise);
         msg!("Amount to raise: {}", stored_amount);
@@ -176,6 +583,7 @@ mod tests {
         msg!("Time started: {}", time_started);
         msg!("Duration: {}", duration_byte);
         msg!("Bump: {}", bump_byte);
+        msg!("Campaign id: {}", stored_campaign_id);
 
         // Verify vault ATA
         msg!("Verifying vault ATA...");
@@ -203,10 +611,49 @@ mod tests {
         msg!("\n\n\n")
     }
 
+    #[test]
+    fn test_initialize_rejects_zero_quorum() {
+        let (mut svm, payer) = setup();
+
+        let (fundraiser_pda, _) = Pubkey::find_program_address(
+            &[b"fundraiser", payer.pubkey().as_ref(), &0u64.to_le_bytes()],
+            &program_id(),
+        );
+        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault", fundraiser_pda.as_ref()], &program_id());
+
+        // With no quorum a single tiny yes vote would pass any proposal
+        let init_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: [
+                vec![0u8],
+                LAMPORTS_PER_SOL.to_le_bytes().to_vec(),
+                vec![1],
+                0u64.to_le_bytes().to_vec(),
+                vec![FundraiserMode::Sol as u8],
+                vec![0, THRESHOLD],
+            ].concat(),
+        };
+
+        let tx = Transaction::new(&[&payer], Message::new(&[init_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err(), "Initialize with a zero quorum should fail");
+        assert!(svm.get_account(&fundraiser_pda).is_none());
+    }
+
     #[test]
     fn test_contribute() {
         let (mut svm, payer) = setup();
         let program_id = program_id();
+        let campaign_id: u64 = 0;
 
         msg!("Starting contribute test...");
 
@@ -220,7 +667,7 @@ mod tests {
         msg!("Mint created: {}", mint_to_raise);
 
         let (fundraiser_pda, _bump) = Pubkey::find_program_address(
-            &[b"fundraiser", payer.pubkey().as_ref()],
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
 
@@ -239,6 +686,9 @@ mod tests {
             vec![0u8],
             amount_to_raise.to_le_bytes().to_vec(),
             vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
         ].concat();
 
         let init_ix = Instruction {
@@ -283,7 +733,7 @@ mod tests {
 
         // Derive contributor PDA
         let (contributor_pda, _) = Pubkey::find_program_address(
-            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref()],
+            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
         msg!("Contributor PDA: {}", contributor_pda);
@@ -335,6 +785,7 @@ mod tests {
     fn test_refund() {
         let (mut svm, payer) = setup();
         let program_id = program_id();
+        let campaign_id: u64 = 0;
 
         msg!("Starting refund test...");
 
@@ -348,7 +799,7 @@ mod tests {
         msg!("Mint created: {}", mint_to_raise);
 
         let (fundraiser_pda, _bump) = Pubkey::find_program_address(
-            &[b"fundraiser", payer.pubkey().as_ref()],
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
 
@@ -365,6 +816,9 @@ mod tests {
             vec![0u8],
             amount_to_raise.to_le_bytes().to_vec(),
             vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
         ].concat();
 
         let init_ix = Instruction {
@@ -404,7 +858,7 @@ mod tests {
         msg!("Minted {} tokens to contributor", contribution_amount * 2);
 
         let (contributor_pda, _) = Pubkey::find_program_address(
-            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref()],
+            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
 
@@ -507,6 +961,7 @@ mod tests {
     fn test_finalize() {
         let (mut svm, payer) = setup();
         let program_id = program_id();
+        let campaign_id: u64 = 0;
 
         msg!("Starting finalize test...");
 
@@ -520,7 +975,7 @@ mod tests {
         msg!("Mint created: {}", mint_to_raise);
 
         let (fundraiser_pda, _bump) = Pubkey::find_program_address(
-            &[b"fundraiser", payer.pubkey().as_ref()],
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
 
@@ -537,6 +992,9 @@ mod tests {
             vec![0u8],
             amount_to_raise.to_le_bytes().to_vec(),
             vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
         ].concat();
 
         let init_ix = Instruction {
@@ -577,7 +1035,7 @@ mod tests {
         msg!("Minted {} tokens to contributor", contribution_amount);
 
         let (contributor1_pda, _) = Pubkey::find_program_address(
-            &[b"contributor", fundraiser_pda.as_ref(), contributor1.pubkey().as_ref()],
+            &[b"contributor", fundraiser_pda.as_ref(), contributor1.pubkey().as_ref(), &campaign_id.to_le_bytes()],
             &program_id,
         );
 
@@ -651,6 +1109,998 @@ mod tests {
         msg!("\n\n\n");
     }
 
-}
+    #[test]
+    fn test_get_status() {
+        let (mut svm, payer) = setup();
+        let program_id = program_id();
+        let campaign_id: u64 = 0;
+
+        msg!("Starting get status test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, _bump) = Pubkey::find_program_address(
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id,
+        );
+
+        let vault_pda = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint_to_raise)
+            .owner(&fundraiser_pda)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let amount_to_raise = MIN_AMOUNT_TO_RAISE + 1_000_000;
+        let duration: u8 = 1;
+        let init_data = [
+            vec![0u8],
+            amount_to_raise.to_le_bytes().to_vec(),
+            vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
+        ].concat();
+
+        let init_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: init_data,
+        };
+
+        let tx = Transaction::new(&[&payer], Message::new(&[init_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        msg!("Fundraiser initialized");
 
+        // Freshly initialized: active for (duration + 1) days, nothing raised
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Active);
+        assert_eq!(report.time_remaining, 2 * 86400);
+        assert_eq!(report.percent_funded, 0);
 
+        // Contribute half of the target
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint_to_raise)
+            .owner(&contributor.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let contribution_amount = amount_to_raise / 2;
+        MintTo::new(&mut svm, &payer, &mint_to_raise, &contributor_ata, contribution_amount)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (contributor_pda, _) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id,
+        );
+
+        let contribute_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new(contributor_ata, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: [vec![1u8], contribution_amount.to_le_bytes().to_vec()].concat(),
+        };
+
+        let tx = Transaction::new(
+            &[&contributor],
+            Message::new(&[contribute_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).unwrap();
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Active);
+        assert_eq!(report.percent_funded, 49);
+
+        // Advance time past the end without reaching the target
+        let mut clock = Clock::default();
+        clock.unix_timestamp = 1700000000 + (86400 * 2);
+        svm.set_sysvar(&clock);
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Failed);
+        assert_eq!(report.time_remaining, 0);
+        assert_eq!(report.percent_funded, 49);
+
+        msg!("Get status test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_status_after_255_days() {
+        let (mut svm, payer) = setup();
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let amount_to_raise = MIN_AMOUNT_TO_RAISE + 1_000_000;
+        let (fundraiser_pda, vault_pda) = initialize_campaign(&mut svm, &payer, &mint_to_raise, 0, amount_to_raise, 1);
+
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint_to_raise)
+            .owner(&contributor.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+        MintTo::new(&mut svm, &payer, &mint_to_raise, &contributor_ata, amount_to_raise)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+        let contributor_pda = contribute(
+            &mut svm, &contributor, &contributor_ata, &mint_to_raise, &fundraiser_pda, &vault_pda, 0, amount_to_raise / 2,
+        );
+
+        // 256 whole days would wrap to 0 if counted in a u8
+        let mut clock = Clock::default();
+        clock.unix_timestamp = 1700000000 + 86400 * 256;
+        svm.set_sysvar(&clock);
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Failed);
+        assert_eq!(report.time_remaining, 0);
+
+        // Still closed to contributions and open for refunds
+        let contribute_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new(contributor_ata, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: [vec![1u8], 1_000u64.to_le_bytes().to_vec()].concat(),
+        };
+        let tx = Transaction::new(
+            &[&contributor],
+            Message::new(&[contribute_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        assert!(svm.send_transaction(tx).is_err(), "Contribute should fail after the campaign ended");
+
+        assert!(refund(
+            &mut svm, &contributor, &payer.pubkey(), &mint_to_raise, &fundraiser_pda, &contributor_pda, &contributor_ata, &vault_pda,
+        ), "Refund should succeed after the campaign ended");
+    }
+
+    #[test]
+    fn test_get_status_succeeded_and_finalized() {
+        let (mut svm, payer) = setup();
+        let program_id = program_id();
+        let campaign_id: u64 = 0;
+
+        msg!("Starting get status (success path) test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, _bump) = Pubkey::find_program_address(
+            &[b"fundraiser", payer.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id,
+        );
+
+        let vault_pda = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint_to_raise)
+            .owner(&fundraiser_pda)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let amount_to_raise = MIN_AMOUNT_TO_RAISE + 1_000_000;
+        let duration: u8 = 30;
+        let init_data = [
+            vec![0u8],
+            amount_to_raise.to_le_bytes().to_vec(),
+            vec![duration],
+            campaign_id.to_le_bytes().to_vec(),
+            vec![FundraiserMode::Token as u8],
+            vec![QUORUM, THRESHOLD],
+        ].concat();
+
+        let init_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: init_data,
+        };
+
+        let tx = Transaction::new(&[&payer], Message::new(&[init_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Fund the full target
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint_to_raise)
+            .owner(&contributor.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        MintTo::new(&mut svm, &payer, &mint_to_raise, &contributor_ata, amount_to_raise)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (contributor_pda, _) = Pubkey::find_program_address(
+            &[b"contributor", fundraiser_pda.as_ref(), contributor.pubkey().as_ref(), &campaign_id.to_le_bytes()],
+            &program_id,
+        );
+
+        let contribute_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new(contributor_ata, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+            ],
+            data: [vec![1u8], amount_to_raise.to_le_bytes().to_vec()].concat(),
+        };
+
+        let tx = Transaction::new(
+            &[&contributor],
+            Message::new(&[contribute_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).unwrap();
+
+        // Target met, but still open for contributions until the end
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Active);
+        assert_eq!(report.percent_funded, 100);
+        assert!(report.time_remaining > 0);
+
+        let mut clock = Clock::default();
+        clock.unix_timestamp = 1700000000 + 86400 * (duration as i64 + 1);
+        svm.set_sysvar(&clock);
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Succeeded);
+        assert_eq!(report.time_remaining, 0);
+
+        // Finalize closes the fundraiser account
+        let maker_ata = spl_associated_token_account::get_associated_token_address(
+            &payer.pubkey(),
+            &mint_to_raise
+        );
+
+        let finalize_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: vec![3u8],
+        };
+
+        let tx = Transaction::new(
+            &[&payer],
+            Message::new(&[finalize_ix], Some(&payer.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).expect("Finalize failed");
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report, StatusReport::FINALIZED);
+
+        msg!("Get status (success path) test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_parallel_campaigns_same_maker() {
+        let (mut svm, payer) = setup();
+
+        msg!("Starting parallel campaigns test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let amount_to_raise = MIN_AMOUNT_TO_RAISE + 1_000_000;
+
+        // Two campaigns from the same maker, distinguished only by campaign id
+        let (fundraiser_a, vault_a) = initialize_campaign(&mut svm, &payer, &mint_to_raise, 1, amount_to_raise, 30);
+        let (fundraiser_b, vault_b) = initialize_campaign(&mut svm, &payer, &mint_to_raise, 2, amount_to_raise * 2, 30);
+        assert_ne!(fundraiser_a, fundraiser_b, "Campaigns should have distinct PDAs");
+        assert_ne!(vault_a, vault_b, "Campaigns should have distinct vaults");
+        msg!("Campaign 1: {}, campaign 2: {}", fundraiser_a, fundraiser_b);
+
+        // Re-using a campaign id is still rejected
+        let init_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_a, false),
+                AccountMeta::new(vault_a, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: [vec![0u8], amount_to_raise.to_le_bytes().to_vec(), vec![30u8], 1u64.to_le_bytes().to_vec(), vec![FundraiserMode::Token as u8], vec![QUORUM, THRESHOLD]].concat(),
+        };
+        svm.expire_blockhash();
+        let tx = Transaction::new(&[&payer], Message::new(&[init_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err(), "Campaign id re-use should fail");
+
+        // One contributor backs both campaigns
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint_to_raise)
+            .owner(&contributor.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        MintTo::new(&mut svm, &payer, &mint_to_raise, &contributor_ata, amount_to_raise * 2)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let contributor_a = contribute(&mut svm, &contributor, &contributor_ata, &mint_to_raise, &fundraiser_a, &vault_a, 1, amount_to_raise);
+        let contributor_b = contribute(&mut svm, &contributor, &contributor_ata, &mint_to_raise, &fundraiser_b, &vault_b, 2, 50_000);
+        assert_ne!(contributor_a, contributor_b, "Contributor records should be per campaign");
+
+        let fundraiser_a_account = svm.get_account(&fundraiser_a).unwrap();
+        let fundraiser_b_account = svm.get_account(&fundraiser_b).unwrap();
+        assert_eq!(u64::from_le_bytes(fundraiser_a_account.data[72..80].try_into().unwrap()), amount_to_raise);
+        assert_eq!(u64::from_le_bytes(fundraiser_b_account.data[72..80].try_into().unwrap()), 50_000);
+
+        // Finalizing campaign 1 leaves campaign 2 untouched
+        let maker_ata = spl_associated_token_account::get_associated_token_address(
+            &payer.pubkey(),
+            &mint_to_raise
+        );
+
+        let finalize_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_a, false),
+                AccountMeta::new(vault_a, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: vec![3u8],
+        };
+
+        let tx = Transaction::new(&[&payer], Message::new(&[finalize_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).expect("Finalize of campaign 1 failed");
+
+        let maker_token = spl_token::state::Account::unpack(&svm.get_account(&maker_ata).unwrap().data).unwrap();
+        assert_eq!(maker_token.amount, amount_to_raise, "Maker should receive campaign 1 funds only");
+
+        let vault_b_token = spl_token::state::Account::unpack(&svm.get_account(&vault_b).unwrap().data).unwrap();
+        assert_eq!(vault_b_token.amount, 50_000, "Campaign 2 vault should be untouched");
+
+        let report = get_status(&mut svm, &payer, &fundraiser_b);
+        assert_eq!(report.status, FundraiserStatus::Active);
+
+        msg!("Parallel campaigns test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_sol_contribute_and_finalize() {
+        let (mut svm, payer) = setup();
+        let program_id = program_id();
+
+        msg!("Starting SOL finalize test...");
+
+        let amount_to_raise = 2 * LAMPORTS_PER_SOL;
+        let (fundraiser_pda, vault_pda) = initialize_sol_campaign(&mut svm, &payer, 0, amount_to_raise, 30);
+
+        // The mode is recorded and the vault is a rent-exempt, program-owned PDA
+        let fundraiser_account = svm.get_account(&fundraiser_pda).unwrap();
+        assert_eq!(fundraiser_account.data[98], FundraiserMode::Sol as u8, "Mode mismatch");
+
+        let rent_reserve = svm.minimum_balance_for_rent_exemption(0);
+        let vault_account = svm.get_account(&vault_pda).unwrap();
+        assert_eq!(vault_account.owner, program_id, "SOL vault should be owned by program");
+        assert_eq!(vault_account.lamports, rent_reserve, "SOL vault should hold only the rent reserve");
+
+        // Contribute the full target in lamports
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        contribute_sol(&mut svm, &contributor, &fundraiser_pda, &vault_pda, 0, amount_to_raise);
+
+        let vault_account = svm.get_account(&vault_pda).unwrap();
+        assert_eq!(vault_account.lamports, rent_reserve + amount_to_raise);
+
+        // Target met, the maker can finalize before the campaign ends
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Active);
+        assert_eq!(report.percent_funded, 100);
+
+        // Finalize with a separate fee payer so the maker's balance only reflects the payout
+        let fundraiser_lamports = svm.get_account(&fundraiser_pda).unwrap().lamports;
+        let maker_before = svm.get_account(&payer.pubkey()).unwrap().lamports;
+
+        let finalize_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+            ],
+            data: vec![3u8],
+        };
+
+        let tx = Transaction::new(
+            &[&contributor, &payer],
+            Message::new(&[finalize_ix], Some(&contributor.pubkey())),
+            svm.latest_blockhash()
+        );
+        let result = svm.send_transaction(tx).expect("Finalize (SOL) failed");
+        msg!("Finalize (SOL) successful! CUs: {}", result.compute_units_consumed);
+
+        let maker_after = svm.get_account(&payer.pubkey()).unwrap().lamports;
+        assert_eq!(
+            maker_after,
+            maker_before + amount_to_raise + rent_reserve + fundraiser_lamports,
+            "Maker should receive contributions plus both reclaimed rent reserves"
+        );
+
+        let vault_lamports = svm.get_account(&vault_pda).map(|a| a.lamports).unwrap_or(0);
+        assert_eq!(vault_lamports, 0, "SOL vault should be closed");
+
+        msg!("SOL finalize test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_sol_refund() {
+        let (mut svm, payer) = setup();
+        let program_id = program_id();
+
+        msg!("Starting SOL refund test...");
+
+        let amount_to_raise = 2 * LAMPORTS_PER_SOL;
+        let (fundraiser_pda, vault_pda) = initialize_sol_campaign(&mut svm, &payer, 7, amount_to_raise, 1);
+        let rent_reserve = svm.minimum_balance_for_rent_exemption(0);
+
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let contribution_amount = LAMPORTS_PER_SOL;
+        let contributor_pda = contribute_sol(&mut svm, &contributor, &fundraiser_pda, &vault_pda, 7, contribution_amount);
+
+        // Advance time past fundraiser duration with the target missed
+        let mut clock = Clock::default();
+        clock.unix_timestamp = 1700000000 + (86400 * 2);
+        svm.set_sysvar(&clock);
+
+        let contributor_before = svm.get_account(&contributor.pubkey()).unwrap().lamports;
+
+        let refund_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(payer.pubkey(), false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data: vec![2u8],
+        };
+
+        // Payer covers the fee so the contributor's balance only reflects the refund
+        let tx = Transaction::new(
+            &[&payer, &contributor],
+            Message::new(&[refund_ix], Some(&payer.pubkey())),
+            svm.latest_blockhash()
+        );
+        svm.send_transaction(tx).expect("Refund (SOL) failed");
+
+        let contributor_after = svm.get_account(&contributor.pubkey()).unwrap().lamports;
+        assert_eq!(contributor_after, contributor_before + contribution_amount, "Contributor should get lamports back");
+
+        let vault_account = svm.get_account(&vault_pda).unwrap();
+        assert_eq!(vault_account.lamports, rent_reserve, "Vault should keep its rent reserve");
+
+        let fundraiser_account = svm.get_account(&fundraiser_pda).unwrap();
+        let current_amount = u64::from_le_bytes(fundraiser_account.data[72..80].try_into().unwrap());
+        assert_eq!(current_amount, 0);
+
+        let contributor_account = svm.get_account(&contributor_pda).unwrap();
+        let contributor_amount = u64::from_le_bytes(contributor_account.data[0..8].try_into().unwrap());
+        assert_eq!(contributor_amount, 0);
+
+        msg!("SOL refund test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_vote_allows_finalize_below_target() {
+        let (mut svm, payer) = setup();
+
+        msg!("Starting vote to finalize test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, vault_pda, [(alice, _alice_ata, alice_pda), (bob, bob_ata, bob_pda)]) =
+            failed_campaign_with_two_contributors(&mut svm, &payer, &mint_to_raise, 400_000, 100_000);
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Failed);
+
+        let proposal_pda = create_proposal(&mut svm, &payer, &fundraiser_pda, 0, ProposalKind::Finalize, 0);
+
+        // Refunds are frozen while the vote is open
+        assert!(
+            !refund(&mut svm, &bob, &payer.pubkey(), &mint_to_raise, &fundraiser_pda, &bob_pda, &bob_ata, &vault_pda),
+            "Refund should fail while a proposal is active"
+        );
+
+        // Alice holds 80% of the raised amount, which decides the vote on its own
+        assert!(cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, true));
+        assert!(
+            !cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, true),
+            "Double vote should fail"
+        );
+
+        assert!(execute_proposal(&mut svm, &payer, &payer.pubkey(), &fundraiser_pda, &proposal_pda));
+        assert!(svm.get_account(&proposal_pda).map(|a| a.lamports).unwrap_or(0) == 0, "Proposal should be closed");
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Succeeded);
+
+        assert!(
+            !refund(&mut svm, &bob, &payer.pubkey(), &mint_to_raise, &fundraiser_pda, &bob_pda, &bob_ata, &vault_pda),
+            "Refund should fail once finalize is approved"
+        );
+
+        // Maker can now finalize below target
+        let maker_ata = spl_associated_token_account::get_associated_token_address(
+            &payer.pubkey(),
+            &mint_to_raise
+        );
+
+        let finalize_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            ],
+            data: vec![3u8],
+        };
+
+        let tx = Transaction::new(&[&payer], Message::new(&[finalize_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).expect("Finalize after vote failed");
+
+        let maker_token = spl_token::state::Account::unpack(&svm.get_account(&maker_ata).unwrap().data).unwrap();
+        assert_eq!(maker_token.amount, 500_000, "Maker should receive everything raised");
+
+        msg!("Vote to finalize test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_vote_rejected_reopens_refunds() {
+        let (mut svm, payer) = setup();
+
+        msg!("Starting rejected vote test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, vault_pda, [(alice, alice_ata, alice_pda), (bob, _bob_ata, bob_pda)]) =
+            failed_campaign_with_two_contributors(&mut svm, &payer, &mint_to_raise, 400_000, 100_000);
+
+        let proposal_pda = create_proposal(&mut svm, &payer, &fundraiser_pda, 0, ProposalKind::Finalize, 0);
+
+        // Bob alone (20%) misses quorum, and Alice votes against
+        assert!(cast_vote(&mut svm, &bob, &fundraiser_pda, &bob_pda, &proposal_pda, true));
+        assert!(
+            !execute_proposal(&mut svm, &payer, &payer.pubkey(), &fundraiser_pda, &proposal_pda),
+            "Execute should wait for the voting period"
+        );
+        assert!(cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, false));
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += VOTING_PERIOD_SECONDS;
+        svm.set_sysvar(&clock);
+
+        assert!(
+            !cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, true),
+            "Voting after the deadline should fail"
+        );
+        assert!(execute_proposal(&mut svm, &payer, &payer.pubkey(), &fundraiser_pda, &proposal_pda));
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Failed);
+
+        assert!(
+            refund(&mut svm, &alice, &payer.pubkey(), &mint_to_raise, &fundraiser_pda, &alice_pda, &alice_ata, &vault_pda),
+            "Refund should succeed after the proposal is rejected"
+        );
+
+        // The maker can't freeze refunds again with another proposal
+        let (_, created) = try_create_proposal(&mut svm, &payer, &fundraiser_pda, 1, ProposalKind::Extend, 7);
+        assert!(!created, "CreateProposal should fail after a rejected vote");
+
+        msg!("Rejected vote test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_proposal_limit() {
+        let (mut svm, payer) = setup();
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, vault_pda, [(alice, _alice_ata, alice_pda), (bob, bob_ata, bob_pda)]) =
+            failed_campaign_with_two_contributors(&mut svm, &payer, &mint_to_raise, 400_000, 100_000);
+
+        // Each passed extension runs out, and the maker asks again
+        for index in 0..MAX_PROPOSALS {
+            let proposal_pda = create_proposal(&mut svm, &payer, &fundraiser_pda, index, ProposalKind::Extend, 1);
+            assert!(cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, true));
+            assert!(execute_proposal(&mut svm, &payer, &payer.pubkey(), &fundraiser_pda, &proposal_pda));
+
+            let mut clock = Clock::default();
+            clock.unix_timestamp = 1700000000 + 86400 * (3 + index as i64);
+            svm.set_sysvar(&clock);
+        }
+
+        let (_, created) = try_create_proposal(&mut svm, &payer, &fundraiser_pda, MAX_PROPOSALS, ProposalKind::Extend, 1);
+        assert!(!created, "CreateProposal should fail past the limit");
+
+        assert!(
+            refund(&mut svm, &bob, &payer.pubkey(), &mint_to_raise, &fundraiser_pda, &bob_pda, &bob_ata, &vault_pda),
+            "Refund should succeed once no more proposals can be opened"
+        );
+    }
+
+    #[test]
+    fn test_vote_extends_duration() {
+        let (mut svm, payer) = setup();
+
+        msg!("Starting vote to extend test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, vault_pda, [(alice, alice_ata, alice_pda), _bob]) =
+            failed_campaign_with_two_contributors(&mut svm, &payer, &mint_to_raise, 400_000, 100_000);
+
+        let proposal_pda = create_proposal(&mut svm, &payer, &fundraiser_pda, 0, ProposalKind::Extend, 5);
+        assert!(cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, true));
+        assert!(execute_proposal(&mut svm, &payer, &payer.pubkey(), &fundraiser_pda, &proposal_pda));
+
+        let fundraiser_account = svm.get_account(&fundraiser_pda).unwrap();
+        assert_eq!(fundraiser_account.data[88], 6, "Duration should be extended by 5 days");
+
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Active);
+
+        // The campaign accepts contributions again
+        MintTo::new(&mut svm, &payer, &mint_to_raise, &alice_ata, 10_000)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+        contribute(&mut svm, &alice, &alice_ata, &mint_to_raise, &fundraiser_pda, &vault_pda, 0, 10_000);
+
+        let fundraiser_account = svm.get_account(&fundraiser_pda).unwrap();
+        let current_amount = u64::from_le_bytes(fundraiser_account.data[72..80].try_into().unwrap());
+        assert_eq!(current_amount, 510_000);
+
+        msg!("Vote to extend test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_extend_executed_after_voting_period() {
+        let (mut svm, payer) = setup();
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, vault_pda, [(alice, alice_ata, alice_pda), _bob]) =
+            failed_campaign_with_two_contributors(&mut svm, &payer, &mint_to_raise, 400_000, 100_000);
+
+        // An extension that would take the duration past 255 days can't be proposed
+        let (_, created) = try_create_proposal(&mut svm, &payer, &fundraiser_pda, 0, ProposalKind::Extend, 255);
+        assert!(!created, "CreateProposal should fail when the duration would overflow");
+
+        let proposal_pda = create_proposal(&mut svm, &payer, &fundraiser_pda, 0, ProposalKind::Extend, 1);
+        assert!(cast_vote(&mut svm, &alice, &fundraiser_pda, &alice_pda, &proposal_pda, true));
+
+        // Executed once the voting period is over, longer than the extension itself
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += VOTING_PERIOD_SECONDS;
+        svm.set_sysvar(&clock);
+        assert!(execute_proposal(&mut svm, &payer, &payer.pubkey(), &fundraiser_pda, &proposal_pda));
+
+        // The day runs from execution, not from the campaign's original end
+        let report = get_status(&mut svm, &payer, &fundraiser_pda);
+        assert_eq!(report.status, FundraiserStatus::Active);
+        assert_eq!(report.time_remaining, 86400);
+
+        MintTo::new(&mut svm, &payer, &mint_to_raise, &alice_ata, 10_000)
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+        contribute(&mut svm, &alice, &alice_ata, &mint_to_raise, &fundraiser_pda, &vault_pda, 0, 10_000);
+    }
+
+    #[test]
+    fn test_crank_refund_batch() {
+        let (mut svm, payer) = setup();
+
+        msg!("Starting crank refund test...");
+
+        let mint_to_raise = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+
+        let (fundraiser_pda, vault_pda, [(alice, alice_ata, alice_pda), (bob, bob_ata, bob_pda)]) =
+            failed_campaign_with_two_contributors(&mut svm, &payer, &mint_to_raise, 400_000, 100_000);
+
+        // Bob contributed everything and closed his now-empty ATA
+        let close_ix = spl_token::instruction::close_account(
+            &spl_token::ID,
+            &bob_ata,
+            &bob.pubkey(),
+            &bob.pubkey(),
+            &[],
+        ).unwrap();
+        let tx = Transaction::new(&[&bob], Message::new(&[close_ix], Some(&bob.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).expect("Closing Bob's ATA failed");
+        assert!(svm.get_account(&bob_ata).map(|a| a.data.is_empty()).unwrap_or(true));
+
+        let cranker = Keypair::new();
+        svm.airdrop(&cranker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+        let contributor_rent = svm.get_account(&alice_pda).unwrap().lamports;
+        let bounty = contributor_rent * CRANK_BOUNTY_PERCENTAGE / 100;
+        let alice_before = svm.get_account(&alice.pubkey()).unwrap().lamports;
+
+        // A crank naming another mint is rejected up front
+        let other_mint = CreateMint::new(&mut svm, &payer)
+            .decimals(6)
+            .authority(&payer.pubkey())
+            .token_program_id(&spl_token::ID)
+            .send()
+            .unwrap();
+        let wrong_mint_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(cranker.pubkey(), true),
+                AccountMeta::new_readonly(other_mint, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+                AccountMeta::new(bob.pubkey(), false),
+                AccountMeta::new(bob_pda, false),
+                AccountMeta::new(spl_associated_token_account::get_associated_token_address(&bob.pubkey(), &other_mint), false),
+            ],
+            data: vec![8u8],
+        };
+        let tx = Transaction::new(&[&cranker], Message::new(&[wrong_mint_ix], Some(&cranker.pubkey())), svm.latest_blockhash());
+        let err = svm.send_transaction(tx).expect_err("CrankRefund with the wrong mint should fail");
+        assert!(format!("{:?}", err.err).contains("Custom(18)"), "Expected InvalidMint, got {:?}", err.err);
+
+        let crank_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(cranker.pubkey(), true),
+                AccountMeta::new_readonly(mint_to_raise, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+                AccountMeta::new(alice.pubkey(), false),
+                AccountMeta::new(alice_pda, false),
+                AccountMeta::new(alice_ata, false),
+                AccountMeta::new(bob.pubkey(), false),
+                AccountMeta::new(bob_pda, false),
+                AccountMeta::new(bob_ata, false),
+            ],
+            data: vec![8u8],
+        };
+
+        let tx = Transaction::new(&[&cranker], Message::new(&[crank_ix.clone()], Some(&cranker.pubkey())), svm.latest_blockhash());
+        let result = svm.send_transaction(tx).expect("CrankRefund failed");
+        msg!("CrankRefund successful! CUs: {}", result.compute_units_consumed);
+
+        // Both contributors are refunded, Bob's ATA recreated along the way
+        let alice_token = spl_token::state::Account::unpack(&svm.get_account(&alice_ata).unwrap().data).unwrap();
+        assert_eq!(alice_token.amount, 400_000);
+        let bob_token = spl_token::state::Account::unpack(&svm.get_account(&bob_ata).unwrap().data).unwrap();
+        assert_eq!(bob_token.amount, 100_000);
+        let vault_token = spl_token::state::Account::unpack(&svm.get_account(&vault_pda).unwrap().data).unwrap();
+        assert_eq!(vault_token.amount, 0);
+
+        let fundraiser_account = svm.get_account(&fundraiser_pda).unwrap();
+        let current_amount = u64::from_le_bytes(fundraiser_account.data[72..80].try_into().unwrap());
+        assert_eq!(current_amount, 0);
+
+        // Contributor PDAs are closed, rent returned minus the cranker's bounty
+        for contributor_pda in [alice_pda, bob_pda] {
+            let lamports = svm.get_account(&contributor_pda).map(|a| a.lamports).unwrap_or(0);
+            assert_eq!(lamports, 0, "Contributor PDA should be closed");
+        }
+        let alice_after = svm.get_account(&alice.pubkey()).unwrap().lamports;
+        assert_eq!(alice_after, alice_before + contributor_rent - bounty);
+
+        // Cranking the same contributors again fails, their records are gone
+        svm.expire_blockhash();
+        let tx = Transaction::new(&[&cranker], Message::new(&[crank_ix], Some(&cranker.pubkey())), svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err(), "Second crank should fail");
+
+        msg!("Crank refund test passed!");
+        msg!("\n\n\n");
+    }
+
+    #[test]
+    fn test_crank_refund_sol() {
+        let (mut svm, payer) = setup();
+
+        msg!("Starting SOL crank refund test...");
+
+        let amount_to_raise = 2 * LAMPORTS_PER_SOL;
+        let (fundraiser_pda, vault_pda) = initialize_sol_campaign(&mut svm, &payer, 0, amount_to_raise, 1);
+        let rent_reserve = svm.minimum_balance_for_rent_exemption(0);
+
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+        let contributor_pda = contribute_sol(&mut svm, &contributor, &fundraiser_pda, &vault_pda, 0, LAMPORTS_PER_SOL);
+
+        let crank_ix = Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+                AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+                AccountMeta::new(contributor.pubkey(), false),
+                AccountMeta::new(contributor_pda, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(pinocchio_system::ID), false),
+            ],
+            data: vec![8u8],
+        };
+
+        // Cranking before the campaign ends is rejected
+        let tx = Transaction::new(&[&payer], Message::new(&[crank_ix.clone()], Some(&payer.pubkey())), svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err(), "Crank should fail while the campaign is active");
+
+        let mut clock = Clock::default();
+        clock.unix_timestamp = 1700000000 + (86400 * 2);
+        svm.set_sysvar(&clock);
+
+        let contributor_rent = svm.get_account(&contributor_pda).unwrap().lamports;
+        let contributor_before = svm.get_account(&contributor.pubkey()).unwrap().lamports;
+
+        svm.expire_blockhash();
+        let tx = Transaction::new(&[&payer], Message::new(&[crank_ix], Some(&payer.pubkey())), svm.latest_blockhash());
+        svm.send_transaction(tx).expect("CrankRefund (SOL) failed");
+
+        // The bounty is fixed by the program, the cranker can't pick a bigger cut
+        let bounty = contributor_rent * CRANK_BOUNTY_PERCENTAGE / 100;
+        let contributor_after = svm.get_account(&contributor.pubkey()).unwrap().lamports;
+        assert_eq!(contributor_after, contributor_before + LAMPORTS_PER_SOL + contributor_rent - bounty);
+
+        let vault_account = svm.get_account(&vault_pda).unwrap();
+        assert_eq!(vault_account.lamports, rent_reserve, "Vault should keep its rent reserve");
+
+        msg!("SOL crank refund test passed!");
+        msg!("\n\n\n");
+    }
+
+}
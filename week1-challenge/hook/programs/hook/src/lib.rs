@@ -2,8 +2,19 @@
 use anchor_lang::{
     prelude::*,
     solana_program::program_error::ProgramError,
+    Discriminator,
+};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_hook::{TransferHook as TransferHookExtension, TransferHookAccount},
+            BaseStateWithExtensions,
+            StateWithExtensions,
+        },
+    },
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use spl_tlv_account_resolution::{
     account::ExtraAccountMeta,
     seeds::Seed,
@@ -14,16 +25,114 @@ use spl_transfer_hook_interface::instruction::{
     TransferHookInstruction
 };
 
+mod tests;
+
 declare_id!("YTRoGAwEK7wZ4Fmi6Pp5QFuKttcqViwBRNnKkgjptzZ");
 
+/// A sender's volume is kept per hour, in one bucket for each hour of the
+/// rolling window their daily limit applies to
+pub const VOLUME_BUCKET_SECONDS: i64 = 60 * 60;
+pub const VOLUME_BUCKETS: usize = 24;
+
+/// Length of the rolling window a sender's daily volume is counted over
+pub const VOLUME_WINDOW_SECONDS: i64 = VOLUME_BUCKET_SECONDS * VOLUME_BUCKETS as i64;
+
 #[program]
 pub mod transfer_hook {
     use super::*;
 
-    pub fn add_to_whitelist(ctx: Context<AddToWhitelist>) -> Result<()> {
+    /// Only the mint's transfer hook authority can set up its config
+    pub fn initialize_config(ctx: Context<InitializeConfig>, admin: Pubkey) -> Result<()> {
+        require_hook_authority(&ctx.accounts.mint, &ctx.accounts.authority.key())?;
+
+        let config = &mut ctx.accounts.config;
+        config.mint = ctx.accounts.mint.key();
+        config.admin = admin;
+        config.bump = ctx.bumps.config;
+        msg!("Whitelist admin for mint {} is {}", config.mint, admin);
+        Ok(())
+    }
+
+    pub fn set_config_admin(ctx: Context<SetConfigAdmin>, admin: Pubkey) -> Result<()> {
+        require_hook_authority(&ctx.accounts.mint, &ctx.accounts.authority.key())?;
+
+        ctx.accounts.config.admin = admin;
+        msg!("Whitelist admin for mint {} is now {}", ctx.accounts.config.mint, admin);
+        Ok(())
+    }
+
+    /// Adds `user` with `role`, or changes the role of an existing entry
+    pub fn add_to_whitelist(ctx: Context<AddToWhitelist>, role: Role) -> Result<()> {
         let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.mint = ctx.accounts.config.mint;
         whitelist.user = ctx.accounts.user.key();
-        msg!("Added user {} to whitelist", whitelist.user);
+        whitelist.role = role;
+        msg!("Added user {} to whitelist of mint {} as {:?}", whitelist.user, whitelist.mint, role);
+
+        init_counter(&mut ctx.accounts.counter, whitelist, ctx.bumps.counter);
+        Ok(())
+    }
+
+    /// Caps what the user can send, 0 means no limit
+    pub fn set_limits(ctx: Context<SetLimits>, max_per_transfer: u64, daily_limit: u64) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.max_per_transfer = max_per_transfer;
+        whitelist.daily_limit = daily_limit;
+        msg!(
+            "User {} limited to {} per transfer and {} per day",
+            whitelist.user,
+            max_per_transfer,
+            daily_limit
+        );
+        Ok(())
+    }
+
+    /// Copies a global entry from before entries were scoped to a mint into
+    /// the config's mint. Entries from before roles existed become `Both`.
+    /// The global entry stays, since every mint's admin may still need it.
+    pub fn migrate_whitelist_entry(ctx: Context<MigrateWhitelistEntry>) -> Result<()> {
+        let legacy = ctx.accounts.legacy_whitelist.to_account_info();
+
+        let role = {
+            let data = legacy.try_borrow_data()?;
+            require!(
+                data.len() >= 8 + 32 && data.starts_with(WhitelistEntry::DISCRIMINATOR),
+                HookError::NotLegacyEntry
+            );
+            match data.get(8 + 32) {
+                Some(role) => Role::try_from_slice(&[*role])?,
+                None => Role::Both,
+            }
+        };
+
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.mint = ctx.accounts.config.mint;
+        whitelist.user = ctx.accounts.user.key();
+        whitelist.role = role;
+
+        init_counter(&mut ctx.accounts.counter, whitelist, ctx.bumps.counter);
+
+        msg!("Migrated user {} to whitelist of mint {} as {:?}", whitelist.user, whitelist.mint, role);
+        Ok(())
+    }
+
+    /// Closes a global entry once every mint that needs it has migrated it.
+    /// Entries aren't tied to any mint, so only the program's upgrade
+    /// authority can do this.
+    pub fn close_legacy_whitelist_entry(ctx: Context<CloseLegacyWhitelistEntry>) -> Result<()> {
+        let legacy = ctx.accounts.legacy_whitelist.to_account_info();
+        require!(
+            legacy.try_borrow_data()?.starts_with(WhitelistEntry::DISCRIMINATOR),
+            HookError::NotLegacyEntry
+        );
+
+        let authority = ctx.accounts.authority.to_account_info();
+        **authority.try_borrow_mut_lamports()? += legacy.lamports();
+        **legacy.try_borrow_mut_lamports()? = 0;
+        legacy.assign(&System::id());
+        legacy.resize(0)?;
+
+        msg!("Closed global whitelist entry of user {}", ctx.accounts.user.key());
         Ok(())
     }
 
@@ -35,22 +144,7 @@ pub mod transfer_hook {
     pub fn initialize_extra_account_meta_list(
         ctx: Context<InitializeExtraAccountMetaList>
     ) -> Result<()> {
-        // Define the whitelist account as an extra account meta
-        // Index 0-3: source_token, mint, destination_token, owner
-        // Index 4: extra_account_meta_list
-        // Index 5: whitelist PDA
-        let account_metas = vec![
-            ExtraAccountMeta::new_with_seeds(
-                &[
-                    Seed::Literal {
-                        bytes: b"hook".to_vec(),
-                    },
-                    Seed::AccountKey { index: 3 }, // owner at index 3
-                ],
-                false, // is_signer
-                false, // is_writable
-            )?,
-        ];
+        let account_metas = extra_account_metas()?;
 
         // Calculate account size
         let account_size = ExtraAccountMetaList::size_of(account_metas.len())? as u64;
@@ -87,19 +181,65 @@ pub mod transfer_hook {
         Ok(())
     }
 
+    /// Rewrites a list set up by an older version of the hook with the
+    /// accounts it resolves now, growing it as needed. Only the mint's
+    /// transfer hook authority can do this
+    pub fn update_extra_account_meta_list(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+        require_hook_authority(&ctx.accounts.mint, &ctx.accounts.authority.key())?;
+
+        let account_metas = extra_account_metas()?;
+        let account_size = ExtraAccountMetaList::size_of(account_metas.len())?;
+        let list = ctx.accounts.extra_account_meta_list.to_account_info();
+
+        // Top up rent before growing
+        let lamports = Rent::get()?.minimum_balance(account_size).saturating_sub(list.lamports());
+        if lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: list.clone(),
+                    },
+                ),
+                lamports,
+            )?;
+        }
+        if list.data_len() < account_size {
+            list.resize(account_size)?;
+        }
+
+        ExtraAccountMetaList::update::<ExecuteInstruction>(&mut list.try_borrow_mut_data()?, &account_metas)?;
+
+        msg!("Updated ExtraAccountMetaList for mint {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
 
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
         msg!("Transfer hook triggered for amount: {}", amount);
 
-        // Check whitelist
-        require!(
-            ctx.accounts.whitelist.user == ctx.accounts.owner.key(),
-            HookError::NotWhitelisted
-        );
+        // Only count volume for real transfers, not someone calling the hook directly
+        require_transferring(&ctx.accounts.source_token)?;
+
+        // Both ends need an entry, with a role that allows their side of the transfer
+        let source_whitelist: WhitelistEntry = load_listed(&ctx.accounts.source_whitelist)?;
+        require!(source_whitelist.role.can_send(), HookError::CannotSend);
+        let destination_whitelist: WhitelistEntry = load_listed(&ctx.accounts.destination_whitelist)?;
+        require!(destination_whitelist.role.can_receive(), HookError::CannotReceive);
+
+        if source_whitelist.max_per_transfer > 0 {
+            require!(amount <= source_whitelist.max_per_transfer, HookError::TransferLimitExceeded);
+        }
+
+        let mut counter: TransferCounter = load_listed(&ctx.accounts.counter)?;
+        counter.record(amount, source_whitelist.daily_limit, Clock::get()?.unix_timestamp)?;
+        counter.try_serialize(&mut &mut ctx.accounts.counter.try_borrow_mut_data()?[..])?;
 
         msg!(
-            "Transfer approved for whitelisted user {}",
-            ctx.accounts.owner.key()
+            "Transfer approved from {} to {}",
+            ctx.accounts.owner.key(),
+            ctx.accounts.destination_token.owner
         );
 
         Ok(())
@@ -124,9 +264,208 @@ pub mod transfer_hook {
     }
 }
 
+/// Accounts the hook resolves on top of the transfer's own
+fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    // Define the whitelist accounts as extra account metas
+    // Index 0-3: source_token, mint, destination_token, owner
+    // Index 4: extra_account_meta_list
+    // Index 5: source owner's whitelist PDA for this mint
+    // Index 6: destination owner's whitelist PDA for this mint
+    // Index 7: source owner's volume counter for this mint
+    Ok(vec![
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"hook".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint at index 1
+                Seed::AccountKey { index: 3 }, // owner at index 3
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"hook".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint at index 1
+                // Owner field of the destination token account
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"counter".to_vec(),
+                },
+                Seed::AccountKey { index: 1 }, // mint at index 1
+                Seed::AccountKey { index: 3 }, // owner at index 3
+            ],
+            false, // is_signer
+            true,  // is_writable
+        )?,
+    ])
+}
+
+/// Checks that `authority` is the transfer hook authority of `mint`, and that
+/// the hook points at this program
+fn require_hook_authority(mint: &InterfaceAccount<Mint>, authority: &Pubkey) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    let data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let hook = mint_state
+        .get_extension::<TransferHookExtension>()
+        .map_err(|_| HookError::MintNotHooked)?;
+
+    require!(Option::<Pubkey>::from(hook.program_id) == Some(crate::ID), HookError::MintNotHooked);
+    require!(Option::<Pubkey>::from(hook.authority) == Some(*authority), HookError::NotHookAuthority);
+    Ok(())
+}
+
+/// Checks the source token account is in the middle of a Token-2022 transfer
+fn require_transferring(source_token: &InterfaceAccount<TokenAccount>) -> Result<()> {
+    let source_info = source_token.to_account_info();
+    let data = source_info.try_borrow_data()?;
+    let account_state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    let hook_account = account_state
+        .get_extension::<TransferHookAccount>()
+        .map_err(|_| HookError::NotTransferring)?;
+
+    require!(bool::from(hook_account.transferring), HookError::NotTransferring);
+    Ok(())
+}
+
+/// Reads a PDA created alongside a whitelist entry, failing with `NotWhitelisted` if it doesn't exist
+fn load_listed<T: AccountDeserialize + Owner>(account: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*account.owner, T::owner(), HookError::NotWhitelisted);
+    T::try_deserialize(&mut &account.try_borrow_data()?[..]).map_err(|_| error!(HookError::NotWhitelisted))
+}
+
+fn init_counter(counter: &mut TransferCounter, whitelist: &WhitelistEntry, bump: u8) {
+    counter.mint = whitelist.mint;
+    counter.user = whitelist.user;
+    counter.bump = bump;
+}
+
+/// Which side of a transfer a whitelisted user may be on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    SendOnly,
+    ReceiveOnly,
+    Both,
+}
+
+impl Role {
+    pub fn can_send(self) -> bool {
+        matches!(self, Role::SendOnly | Role::Both)
+    }
+
+    pub fn can_receive(self) -> bool {
+        matches!(self, Role::ReceiveOnly | Role::Both)
+    }
+}
+
+/// Lets `user` take part in transfers of `mint`
 #[account]
 pub struct WhitelistEntry {
+    pub mint: Pubkey,
     pub user: Pubkey,
+    pub role: Role,
+    /// Most the user can send in one transfer, 0 for no limit
+    pub max_per_transfer: u64,
+    /// Most the user can send in any `VOLUME_WINDOW_SECONDS`, to the hour, 0
+    /// for no limit
+    pub daily_limit: u64,
+}
+
+/// What `user` has sent of `mint` over the last `VOLUME_WINDOW_SECONDS`
+#[account]
+pub struct TransferCounter {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    /// Hour of the latest transfer, counted in hours since the epoch
+    pub current_hour: i64,
+    /// Volume sent in each hour of the window, indexed by hour modulo
+    /// `VOLUME_BUCKETS`
+    pub buckets: [u64; VOLUME_BUCKETS],
+    pub bump: u8,
+}
+
+impl TransferCounter {
+    /// Volume sent over the window ending with `current_hour`
+    pub fn volume(&self) -> u64 {
+        self.buckets.iter().fold(0, |total, bucket| total.saturating_add(*bucket))
+    }
+
+    pub fn record(&mut self, amount: u64, daily_limit: u64, now: i64) -> Result<()> {
+        // Empty the buckets of hours that left the window since the last transfer
+        let hour = now.div_euclid(VOLUME_BUCKET_SECONDS);
+        let elapsed = hour.saturating_sub(self.current_hour).clamp(0, VOLUME_BUCKETS as i64);
+        for step in 1..=elapsed {
+            self.buckets[Self::bucket(self.current_hour + step)] = 0;
+        }
+        self.current_hour = self.current_hour.max(hour);
+
+        if daily_limit > 0 {
+            let volume = self.volume().checked_add(amount).ok_or(HookError::DailyLimitExceeded)?;
+            require!(volume <= daily_limit, HookError::DailyLimitExceeded);
+        }
+
+        let bucket = &mut self.buckets[Self::bucket(self.current_hour)];
+        *bucket = bucket.checked_add(amount).ok_or(HookError::DailyLimitExceeded)?;
+
+        Ok(())
+    }
+
+    fn bucket(hour: i64) -> usize {
+        hour.rem_euclid(VOLUME_BUCKETS as i64) as usize
+    }
+}
+
+/// Per-mint settings, the admin manages the whitelist
+#[account]
+pub struct HookConfig {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// The mint's transfer hook authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        space = 8 + 32 + 32 + 1,
+        payer = authority,
+        seeds = [b"config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetConfigAdmin<'info> {
+    /// The mint's transfer hook authority
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, HookConfig>,
 }
 
 #[derive(Accounts)]
@@ -146,37 +485,178 @@ pub struct InitializeExtraAccountMetaList<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    /// The mint's transfer hook authority, pays for the list to grow
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: List created by `initialize_extra_account_meta_list`, rewritten
+    /// in the handler
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct AddToWhitelist<'info> {
     #[account(
-        init,
-        space = 8 + 32,
-        payer = authority,
-        seeds = [b"hook", user.key().as_ref()],
+        init_if_needed,
+        space = 8 + 32 + 32 + 1 + 8 + 8,
+        payer = admin,
+        seeds = [b"hook", config.mint.as_ref(), user.key().as_ref()],
         bump
     )]
     pub whitelist: Account<'info, WhitelistEntry>,
 
+    #[account(
+        init_if_needed,
+        space = 8 + 32 + 32 + 8 + 8 * VOLUME_BUCKETS + 1,
+        payer = admin,
+        seeds = [b"counter", config.mint.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, TransferCounter>,
+
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ HookError::Unauthorized,
+    )]
+    pub config: Account<'info, HookConfig>,
 
     /// CHECK: User being added to whitelist
     pub user: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateWhitelistEntry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ HookError::Unauthorized,
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Global entry from before entries were scoped to a mint, its
+    /// layout is checked in the handler
+    #[account(
+        seeds = [b"hook", user.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub legacy_whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: User whose entry is migrated
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        init,
+        space = 8 + 32 + 32 + 1 + 8 + 8,
+        payer = admin,
+        seeds = [b"hook", config.mint.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+
+    #[account(
+        init_if_needed,
+        space = 8 + 32 + 32 + 8 + 8 * VOLUME_BUCKETS + 1,
+        payer = admin,
+        seeds = [b"counter", config.mint.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, TransferCounter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLegacyWhitelistEntry<'info> {
+    /// The program's upgrade authority, gets the entry's rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ HookError::NotUpgradeAuthority)]
+    pub program: Program<'info, crate::program::TransferHook>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ HookError::NotUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// CHECK: Global entry from before entries were scoped to a mint, its
+    /// discriminator is checked in the handler
+    #[account(
+        mut,
+        seeds = [b"hook", user.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub legacy_whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: User whose entry is closed
+    pub user: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ HookError::Unauthorized,
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"hook", config.mint.as_ref(), whitelist.user.as_ref()],
+        bump
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+}
+
 #[derive(Accounts)]
 pub struct RemoveFromWhitelist<'info> {
     #[account(
         mut,
-        close = authority,
-        seeds = [b"hook", whitelist.user.as_ref()],
+        close = admin,
+        seeds = [b"hook", config.mint.as_ref(), whitelist.user.as_ref()],
         bump
     )]
     pub whitelist: Account<'info, WhitelistEntry>,
 
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"counter", config.mint.as_ref(), whitelist.user.as_ref()],
+        bump = counter.bump,
+    )]
+    pub counter: Account<'info, TransferCounter>,
+
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = admin @ HookError::Unauthorized,
+    )]
+    pub config: Account<'info, HookConfig>,
 }
 
 
@@ -209,16 +689,54 @@ pub struct TransferHook<'info> {
     // Index 4
     pub extra_account_meta_list: UncheckedAccount<'info>,
 
+    /// CHECK: Source owner's whitelist entry, loaded in the handler so a missing one is `NotWhitelisted`
     #[account(
-        seeds = [b"hook", owner.key().as_ref()],
+        seeds = [b"hook", mint.key().as_ref(), owner.key().as_ref()],
         bump
     )]
     // Index 5: Custom extra account
-    pub whitelist: Account<'info, WhitelistEntry>,
+    pub source_whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: Destination owner's whitelist entry, loaded in the handler
+    #[account(
+        seeds = [b"hook", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump
+    )]
+    // Index 6: Custom extra account
+    pub destination_whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: Source owner's volume counter, loaded and written back in the handler
+    #[account(
+        mut,
+        seeds = [b"counter", mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    // Index 7: Custom extra account
+    pub counter: UncheckedAccount<'info>,
 }
 
 #[error_code]
 pub enum HookError {
     #[msg("User is not whitelisted.")]
     NotWhitelisted,
+    #[msg("Mint's transfer hook doesn't point at this program.")]
+    MintNotHooked,
+    #[msg("Signer is not the mint's transfer hook authority.")]
+    NotHookAuthority,
+    #[msg("Signer is not the whitelist admin.")]
+    Unauthorized,
+    #[msg("Source owner is not allowed to send.")]
+    CannotSend,
+    #[msg("Destination owner is not allowed to receive.")]
+    CannotReceive,
+    #[msg("Account is not a global whitelist entry.")]
+    NotLegacyEntry,
+    #[msg("Hook called outside of a transfer.")]
+    NotTransferring,
+    #[msg("Amount exceeds the sender's per-transfer limit.")]
+    TransferLimitExceeded,
+    #[msg("Amount exceeds what's left of the sender's daily limit.")]
+    DailyLimitExceeded,
+    #[msg("Signer is not the program's upgrade authority.")]
+    NotUpgradeAuthority,
 }
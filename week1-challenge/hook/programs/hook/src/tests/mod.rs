@@ -1,17 +1,24 @@
 #[cfg(test)]
 mod tests {
-    use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
-    use anchor_lang::prelude::msg;
+    use anchor_lang::{prelude::Clock, AccountDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas};
     use {
         litesvm::LiteSVM,
+        solana_account::Account,
+        solana_instruction::{AccountMeta, Instruction},
         solana_keypair::Keypair,
         solana_native_token::LAMPORTS_PER_SOL,
         solana_pubkey::Pubkey,
+        solana_sdk_ids::{bpf_loader_upgradeable::ID as BPF_LOADER_UPGRADEABLE_ID, system_program::ID as SYSTEM_PROGRAM_ID},
         solana_signer::Signer,
-        solana_instruction::Instruction,
         solana_transaction::Transaction,
+        spl_token_2022::{
+            extension::{transfer_hook, ExtensionType},
+            state::{Account as TokenAccountState, Mint as MintState},
+        },
         std::path::PathBuf,
-        crate::{WhitelistEntry, HookError},
+        spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList},
+        spl_transfer_hook_interface::instruction::ExecuteInstruction,
+        crate::{HookConfig, HookError, Role, TransferCounter, WhitelistEntry, VOLUME_BUCKET_SECONDS, VOLUME_WINDOW_SECONDS},
     };
 
     static PROGRAM_ID: Pubkey = crate::ID;
@@ -22,185 +29,722 @@ mod tests {
         program.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
 
         // Load the compiled program
+        program.add_program(PROGRAM_ID, &read_program_so());
+        (program, payer)
+    }
+
+    fn read_program_so() -> Vec<u8> {
         let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("../../target/deploy/hook.so");
-        let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
-        program.add_program(PROGRAM_ID, &program_data);
-        (program, payer)
+        std::fs::read(so_path).expect("Failed to read program SO file")
+    }
+
+    fn derive_program_data() -> Pubkey {
+        Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+    }
+
+    /// Redeploys the program under the upgradeable loader, with `authority`
+    /// as its upgrade authority
+    fn set_upgrade_authority(program: &mut LiteSVM, authority: &Pubkey) {
+        let program_data = derive_program_data();
+
+        // UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }, then the ELF
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&read_program_so());
+        program.set_account(program_data, Account {
+            lamports: program.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+
+        // UpgradeableLoaderState::Program { programdata_address }
+        let mut data = vec![2, 0, 0, 0];
+        data.extend_from_slice(program_data.as_ref());
+        program.set_account(PROGRAM_ID, Account {
+            lamports: program.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: true,
+            rent_epoch: 0,
+        }).unwrap();
+    }
+
+    fn send(program: &mut LiteSVM, signers: &[&Keypair], ixs: &[Instruction]) -> Result<(), String> {
+        program.expire_blockhash();
+        let bh = program.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&signers[0].pubkey()), signers, bh);
+        program.send_transaction(tx)
+            .map(|_| ())
+            .map_err(|e| format!("{:?}", e.err))
     }
 
-    fn derive_whitelist_pda(user: &Pubkey) -> (Pubkey, u8) {
+    fn assert_hook_error(result: Result<(), String>, error: HookError) {
+        let err = result.expect_err("Expected the transaction to fail");
+        let code = format!("Custom({})", u32::from(error));
+        assert!(err.contains(&code), "Expected {}, got {}", code, err);
+    }
+
+    fn funded_keypair(program: &mut LiteSVM) -> Keypair {
+        let keypair = Keypair::new();
+        program.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        keypair
+    }
+
+    fn derive_whitelist_pda(mint: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"hook", mint.as_ref(), user.as_ref()], &PROGRAM_ID)
+    }
+
+    fn derive_counter_pda(mint: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"counter", mint.as_ref(), user.as_ref()], &PROGRAM_ID)
+    }
+
+    fn derive_legacy_whitelist_pda(user: &Pubkey) -> (Pubkey, u8) {
         Pubkey::find_program_address(&[b"hook", user.as_ref()], &PROGRAM_ID)
     }
 
-    #[test]
-    fn test_add_to_whitelist() {
-        let (mut program, payer) = setup();
-        let user = Keypair::new();
-        let (whitelist_pda, _bump) = derive_whitelist_pda(&user.pubkey());
+    fn derive_config_pda(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"config", mint.as_ref()], &PROGRAM_ID)
+    }
+
+    fn derive_extra_account_meta_list(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &PROGRAM_ID).0
+    }
+
+    /// Creates a Token-2022 mint hooked to this program, `payer` is the mint
+    /// and hook authority
+    fn create_mint(program: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+        let mint = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::TransferHook]).unwrap();
+        let rent = program.minimum_balance_for_rent_exemption(space);
+
+        send(program, &[payer, &mint], &[
+            solana_system_interface::instruction::create_account(
+                &payer.pubkey(), &mint.pubkey(), rent, space as u64, &spl_token_2022::ID,
+            ),
+            transfer_hook::instruction::initialize(
+                &spl_token_2022::ID, &mint.pubkey(), Some(payer.pubkey()), Some(PROGRAM_ID),
+            ).unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::ID, &mint.pubkey(), &payer.pubkey(), None, 6,
+            ).unwrap(),
+        ]).expect("Mint creation failed");
+
+        mint.pubkey()
+    }
+
+    /// Creates a token account for `owner` holding `amount`
+    fn create_token_account(program: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<TokenAccountState>(&[ExtensionType::TransferHookAccount])
+            .unwrap();
+        let rent = program.minimum_balance_for_rent_exemption(space);
+
+        send(program, &[payer, &account], &[
+            solana_system_interface::instruction::create_account(
+                &payer.pubkey(), &account.pubkey(), rent, space as u64, &spl_token_2022::ID,
+            ),
+            spl_token_2022::instruction::initialize_account3(
+                &spl_token_2022::ID, &account.pubkey(), mint, owner,
+            ).unwrap(),
+            spl_token_2022::instruction::mint_to(
+                &spl_token_2022::ID, mint, &account.pubkey(), &payer.pubkey(), &[], amount,
+            ).unwrap(),
+        ]).expect("Token account creation failed");
 
-        // Build and send AddToWhitelist ix
+        account.pubkey()
+    }
+
+    fn initialize_config(program: &mut LiteSVM, authority: &Keypair, mint: &Pubkey, admin: &Pubkey) -> Result<(), String> {
         let ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::AddToWhitelist {
-                whitelist: whitelist_pda,
-                authority: payer.pubkey(),
-                user: user.pubkey(),
-                system_program: anchor_lang::system_program::ID,
+            accounts: crate::accounts::InitializeConfig {
+                authority: authority.pubkey(),
+                mint: *mint,
+                config: derive_config_pda(mint).0,
+                system_program: SYSTEM_PROGRAM_ID,
             }
                 .to_account_metas(None),
-            data: crate::instruction::AddToWhitelist {}.data(),
+            data: crate::instruction::InitializeConfig { admin: *admin }.data(),
         };
-
-        let blockhash = program.latest_blockhash();
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&payer.pubkey()),
-            &[&payer],
-            blockhash,
-        );
-        program.send_transaction(tx).expect("Add whitelist failed");
-
-        // Verify PDA contents
-        let whitelist_data = program.get_account(&whitelist_pda).expect("Whitelist PDA not found");
-        let whitelist_entry = WhitelistEntry::try_deserialize(&mut whitelist_data.data.as_slice())
-            .expect("Failed to deserialize WhitelistEntry");
-
-        assert_eq!(whitelist_entry.user, user.pubkey());
-        println!("Added user {} to whitelist successfully!", user.pubkey());
+        send(program, &[authority], &[ix])
     }
 
-    #[test]
-    fn test_validate_transfer_passes_for_whitelisted_user() {
-        let (mut program, payer) = setup();
-        let user = Keypair::new();
+    fn initialize_extra_account_meta_list(program: &mut LiteSVM, payer: &Keypair, mint: &Pubkey) {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeExtraAccountMetaList {
+                payer: payer.pubkey(),
+                extra_account_meta_list: derive_extra_account_meta_list(mint),
+                mint: *mint,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+                .to_account_metas(None),
+            data: crate::instruction::InitializeExtraAccountMetaList {}.data(),
+        };
+        send(program, &[payer], &[ix]).expect("ExtraAccountMetaList init failed");
+    }
 
-        // Derive the whitelist PDA
-        let (whitelist_pda, _bump) =
-            Pubkey::find_program_address(&[b"hook", user.pubkey().as_ref()], &PROGRAM_ID);
+    /// Sets up a hooked mint with `payer` as its whitelist admin
+    fn setup_mint(program: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+        let mint = create_mint(program, payer);
+        initialize_config(program, payer, &mint, &payer.pubkey()).expect("Config init failed");
+        initialize_extra_account_meta_list(program, payer, &mint);
+        mint
+    }
 
-        //  Add user to whitelist via instruction
-        let ix_add = Instruction {
+    fn add_to_whitelist(program: &mut LiteSVM, admin: &Keypair, mint: &Pubkey, user: &Pubkey, role: Role) -> Result<(), String> {
+        let ix = Instruction {
             program_id: PROGRAM_ID,
             accounts: crate::accounts::AddToWhitelist {
-                whitelist: whitelist_pda,
-                authority: payer.pubkey(),
-                user: user.pubkey(),
-                system_program: anchor_lang::system_program::ID,
+                whitelist: derive_whitelist_pda(mint, user).0,
+                counter: derive_counter_pda(mint, user).0,
+                admin: admin.pubkey(),
+                config: derive_config_pda(mint).0,
+                user: *user,
+                system_program: SYSTEM_PROGRAM_ID,
             }
                 .to_account_metas(None),
-            data: crate::instruction::AddToWhitelist {}.data(),
+            data: crate::instruction::AddToWhitelist { role }.data(),
         };
+        send(program, &[admin], &[ix])
+    }
 
-        let bh = program.latest_blockhash();
-        let tx_add =
-            Transaction::new_signed_with_payer(&[ix_add], Some(&payer.pubkey()), &[&payer], bh);
-        program.send_transaction(tx_add).expect("Add to whitelist failed");
+    fn remove_from_whitelist(program: &mut LiteSVM, admin: &Keypair, mint: &Pubkey, user: &Pubkey) -> Result<(), String> {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::RemoveFromWhitelist {
+                whitelist: derive_whitelist_pda(mint, user).0,
+                counter: derive_counter_pda(mint, user).0,
+                admin: admin.pubkey(),
+                config: derive_config_pda(mint).0,
+            }
+                .to_account_metas(None),
+            data: crate::instruction::RemoveFromWhitelist {}.data(),
+        };
+        send(program, &[admin], &[ix])
+    }
 
-        // Validate transfer should succeed
-        let ix_validate = Instruction {
+    fn set_limits(
+        program: &mut LiteSVM,
+        admin: &Keypair,
+        mint: &Pubkey,
+        user: &Pubkey,
+        max_per_transfer: u64,
+        daily_limit: u64,
+    ) -> Result<(), String> {
+        let ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::ValidateTransfer {
-                from: user.pubkey(),
-                to: payer.pubkey(),
-                whitelist: whitelist_pda,
+            accounts: crate::accounts::SetLimits {
+                admin: admin.pubkey(),
+                config: derive_config_pda(mint).0,
+                whitelist: derive_whitelist_pda(mint, user).0,
             }
                 .to_account_metas(None),
-            data: crate::instruction::ValidateTransfer {}.data(),
+            data: crate::instruction::SetLimits { max_per_transfer, daily_limit }.data(),
         };
+        send(program, &[admin], &[ix])
+    }
 
-        let bh = program.latest_blockhash();
+    fn read_whitelist(program: &LiteSVM, mint: &Pubkey, user: &Pubkey) -> Option<WhitelistEntry> {
+        let account = program.get_account(&derive_whitelist_pda(mint, user).0)?;
+        WhitelistEntry::try_deserialize(&mut account.data.as_slice()).ok()
+    }
 
-        // Sign with BOTH the payer (for fees) AND the whitelisted user (required signer)
-        let tx_validate =
-            Transaction::new_signed_with_payer(&[ix_validate], Some(&payer.pubkey()), &[&payer, &user], bh);
+    fn read_counter(program: &LiteSVM, mint: &Pubkey, user: &Pubkey) -> Option<TransferCounter> {
+        let account = program.get_account(&derive_counter_pda(mint, user).0)?;
+        TransferCounter::try_deserialize(&mut account.data.as_slice()).ok()
+    }
 
-        program.send_transaction(tx_validate).expect("Validate transfer failed");
+    fn set_clock(program: &mut LiteSVM, unix_timestamp: i64) {
+        let mut clock = program.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        program.set_sysvar::<Clock>(&clock);
+    }
 
-        println!("Transfer validated successfully for whitelisted user!");
+    fn read_config(program: &LiteSVM, mint: &Pubkey) -> HookConfig {
+        let account = program.get_account(&derive_config_pda(mint).0).expect("Config not found");
+        HookConfig::try_deserialize(&mut account.data.as_slice()).expect("Failed to deserialize HookConfig")
     }
 
+    /// `transfer_checked` from `owner`'s `source` to `destination`, owned by
+    /// `destination_owner`, with the hook's extra accounts
+    fn transfer(
+        program: &mut LiteSVM,
+        owner: &Keypair,
+        mint: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        destination_owner: &Pubkey,
+        amount: u64,
+    ) -> Result<(), String> {
+        let mut ix = spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID, source, mint, destination, &owner.pubkey(), &[], amount, 6,
+        ).unwrap();
+        ix.accounts.extend([
+            AccountMeta::new_readonly(derive_whitelist_pda(mint, &owner.pubkey()).0, false),
+            AccountMeta::new_readonly(derive_whitelist_pda(mint, destination_owner).0, false),
+            AccountMeta::new(derive_counter_pda(mint, &owner.pubkey()).0, false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+            AccountMeta::new_readonly(derive_extra_account_meta_list(mint), false),
+        ]);
+        send(program, &[owner], &[ix])
+    }
 
     #[test]
-    fn test_validate_transfer_fails_for_non_whitelisted_user() {
-        let (mut program, _payer) = setup();
-        let user = Keypair::new();
-        let (whitelist_pda, _bump) = derive_whitelist_pda(&user.pubkey());
+    fn test_initialize_config_requires_hook_authority() {
+        let (mut program, payer) = setup();
+        let mint = create_mint(&mut program, &payer);
+        let stranger = funded_keypair(&mut program);
+
+        let result = initialize_config(&mut program, &stranger, &mint, &stranger.pubkey());
+        assert_hook_error(result, HookError::NotHookAuthority);
+
+        let admin = Pubkey::new_unique();
+        initialize_config(&mut program, &payer, &mint, &admin).expect("Config init failed");
 
-        // Do NOT add to whitelist
-        let ix_validate = Instruction {
+        let config = read_config(&program, &mint);
+        assert_eq!((config.mint, config.admin), (mint, admin));
+    }
+
+    #[test]
+    fn test_set_config_admin() {
+        let (mut program, payer) = setup();
+        let mint = setup_mint(&mut program, &payer);
+        let new_admin = funded_keypair(&mut program);
+        let user = Pubkey::new_unique();
+
+        let set_admin = |signer: &Keypair| Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::ValidateTransfer {
-                from: user.pubkey(),
-                to: Pubkey::new_unique(),
-                whitelist: whitelist_pda,
+            accounts: crate::accounts::SetConfigAdmin {
+                authority: signer.pubkey(),
+                mint,
+                config: derive_config_pda(&mint).0,
             }
                 .to_account_metas(None),
-            data: crate::instruction::ValidateTransfer {}.data(),
+            data: crate::instruction::SetConfigAdmin { admin: new_admin.pubkey() }.data(),
         };
 
-        let bh = program.latest_blockhash();
-        let tx_validate = Transaction::new_signed_with_payer(
-            &[ix_validate],
-            Some(&user.pubkey()),
-            &[&user],
-            bh,
-        );
+        // Only the hook authority picks the admin
+        let result = send(&mut program, &[&new_admin], &[set_admin(&new_admin)]);
+        assert_hook_error(result, HookError::NotHookAuthority);
+        send(&mut program, &[&payer], &[set_admin(&payer)]).expect("Set admin failed");
+        assert_eq!(read_config(&program, &mint).admin, new_admin.pubkey());
+
+        // The hook authority itself is no longer the admin
+        let result = add_to_whitelist(&mut program, &payer, &mint, &user, Role::Both);
+        assert_hook_error(result, HookError::Unauthorized);
+        add_to_whitelist(&mut program, &new_admin, &mint, &user, Role::Both).expect("Add whitelist failed");
+    }
+
+    #[test]
+    fn test_add_to_whitelist() {
+        let (mut program, payer) = setup();
+        let mint = setup_mint(&mut program, &payer);
+        let stranger = funded_keypair(&mut program);
+        let user = Keypair::new();
+
+        let result = add_to_whitelist(&mut program, &stranger, &mint, &user.pubkey(), Role::Both);
+        assert_hook_error(result, HookError::Unauthorized);
+        assert!(read_whitelist(&program, &mint, &user.pubkey()).is_none());
 
-        let err = program.send_transaction(tx_validate).unwrap_err();
-        let err_str = format!("{:?}", err);
-        assert!(
-            err_str.contains("AccountNotFound") || err_str.contains("NotWhitelisted"),
-            "Expected NotWhitelisted, got: {}",
-            err_str
+        add_to_whitelist(&mut program, &payer, &mint, &user.pubkey(), Role::SendOnly).expect("Add whitelist failed");
+        let whitelist_entry = read_whitelist(&program, &mint, &user.pubkey()).expect("Whitelist PDA not found");
+        assert_eq!(
+            (whitelist_entry.mint, whitelist_entry.user, whitelist_entry.role),
+            (mint, user.pubkey(), Role::SendOnly)
         );
-        println!("Non-whitelisted user correctly rejected!");
+        assert_eq!((whitelist_entry.max_per_transfer, whitelist_entry.daily_limit), (0, 0));
+
+        let counter = read_counter(&program, &mint, &user.pubkey()).expect("Counter PDA not found");
+        assert_eq!((counter.mint, counter.user, counter.volume()), (mint, user.pubkey(), 0));
+
+        // Adding again changes the role
+        add_to_whitelist(&mut program, &payer, &mint, &user.pubkey(), Role::Both).expect("Update role failed");
+        assert_eq!(read_whitelist(&program, &mint, &user.pubkey()).unwrap().role, Role::Both);
     }
 
     #[test]
     fn test_remove_from_whitelist() {
         let (mut program, payer) = setup();
+        let mint = setup_mint(&mut program, &payer);
+        let stranger = funded_keypair(&mut program);
         let user = Keypair::new();
-        let (whitelist_pda, _bump) = derive_whitelist_pda(&user.pubkey());
 
-        // Add to whitelist first
-        let ix_add = Instruction {
+        add_to_whitelist(&mut program, &payer, &mint, &user.pubkey(), Role::Both).expect("Add whitelist failed");
+
+        let result = remove_from_whitelist(&mut program, &stranger, &mint, &user.pubkey());
+        assert_hook_error(result, HookError::Unauthorized);
+        assert!(read_whitelist(&program, &mint, &user.pubkey()).is_some());
+
+        remove_from_whitelist(&mut program, &payer, &mint, &user.pubkey()).expect("Remove failed");
+
+        // PDAs should now fail to deserialize
+        assert!(read_whitelist(&program, &mint, &user.pubkey()).is_none(), "Whitelist PDA should be closed");
+        assert!(read_counter(&program, &mint, &user.pubkey()).is_none(), "Counter PDA should be closed");
+    }
+
+    #[test]
+    fn test_transfer_checks_both_roles() {
+        let (mut program, payer) = setup();
+        let mint = setup_mint(&mut program, &payer);
+
+        let sender = funded_keypair(&mut program);
+        let receiver = funded_keypair(&mut program);
+        let outsider = funded_keypair(&mut program);
+        add_to_whitelist(&mut program, &payer, &mint, &sender.pubkey(), Role::SendOnly).unwrap();
+        add_to_whitelist(&mut program, &payer, &mint, &receiver.pubkey(), Role::ReceiveOnly).unwrap();
+
+        let sender_tokens = create_token_account(&mut program, &payer, &mint, &sender.pubkey(), 100);
+        let receiver_tokens = create_token_account(&mut program, &payer, &mint, &receiver.pubkey(), 100);
+        let outsider_tokens = create_token_account(&mut program, &payer, &mint, &outsider.pubkey(), 100);
+
+        transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 10)
+            .expect("Transfer failed");
+
+        // Neither can take the other side
+        let result = transfer(&mut program, &receiver, &mint, &receiver_tokens, &sender_tokens, &sender.pubkey(), 10);
+        assert_hook_error(result, HookError::CannotSend);
+
+        add_to_whitelist(&mut program, &payer, &mint, &receiver.pubkey(), Role::Both).unwrap();
+        let result = transfer(&mut program, &receiver, &mint, &receiver_tokens, &sender_tokens, &sender.pubkey(), 10);
+        assert_hook_error(result, HookError::CannotReceive);
+
+        // Both ends need an entry
+        let result = transfer(&mut program, &sender, &mint, &sender_tokens, &outsider_tokens, &outsider.pubkey(), 10);
+        assert_hook_error(result, HookError::NotWhitelisted);
+        let result = transfer(&mut program, &outsider, &mint, &outsider_tokens, &receiver_tokens, &receiver.pubkey(), 10);
+        assert_hook_error(result, HookError::NotWhitelisted);
+    }
+
+    #[test]
+    fn test_whitelist_is_scoped_to_mint() {
+        let (mut program, payer) = setup();
+        let mint_x = setup_mint(&mut program, &payer);
+        let mint_y = setup_mint(&mut program, &payer);
+
+        let sender = funded_keypair(&mut program);
+        let receiver = funded_keypair(&mut program);
+        for user in [&sender, &receiver] {
+            add_to_whitelist(&mut program, &payer, &mint_x, &user.pubkey(), Role::Both).unwrap();
+        }
+
+        let sender_x = create_token_account(&mut program, &payer, &mint_x, &sender.pubkey(), 100);
+        let receiver_x = create_token_account(&mut program, &payer, &mint_x, &receiver.pubkey(), 0);
+        let sender_y = create_token_account(&mut program, &payer, &mint_y, &sender.pubkey(), 100);
+        let receiver_y = create_token_account(&mut program, &payer, &mint_y, &receiver.pubkey(), 0);
+
+        transfer(&mut program, &sender, &mint_x, &sender_x, &receiver_x, &receiver.pubkey(), 10)
+            .expect("Transfer of mint X failed");
+
+        // Whitelisted for X says nothing about Y
+        let result = transfer(&mut program, &sender, &mint_y, &sender_y, &receiver_y, &receiver.pubkey(), 10);
+        assert_hook_error(result, HookError::NotWhitelisted);
+        assert!(read_whitelist(&program, &mint_y, &sender.pubkey()).is_none());
+
+        // Until mint Y's admin whitelists them too
+        for user in [&sender, &receiver] {
+            add_to_whitelist(&mut program, &payer, &mint_y, &user.pubkey(), Role::Both).unwrap();
+        }
+        transfer(&mut program, &sender, &mint_y, &sender_y, &receiver_y, &receiver.pubkey(), 10)
+            .expect("Transfer of mint Y failed");
+    }
+
+    /// Plants a global `[b"hook", user]` entry as created before entries were
+    /// scoped to a mint
+    fn set_legacy_entry(program: &mut LiteSVM, user: &Pubkey, role: Option<Role>) -> Pubkey {
+        let mut data = WhitelistEntry::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(user.as_ref());
+        if let Some(role) = role {
+            role.serialize(&mut data).unwrap();
+        }
+
+        let (legacy, _) = derive_legacy_whitelist_pda(user);
+        program.set_account(legacy, Account {
+            lamports: program.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+        legacy
+    }
+
+    fn migrate(program: &mut LiteSVM, admin: &Keypair, mint: &Pubkey, user: &Pubkey) -> Result<(), String> {
+        let ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::AddToWhitelist {
-                whitelist: whitelist_pda,
-                authority: payer.pubkey(),
-                user: user.pubkey(),
-                system_program: anchor_lang::system_program::ID,
+            accounts: crate::accounts::MigrateWhitelistEntry {
+                admin: admin.pubkey(),
+                config: derive_config_pda(mint).0,
+                legacy_whitelist: derive_legacy_whitelist_pda(user).0,
+                user: *user,
+                whitelist: derive_whitelist_pda(mint, user).0,
+                counter: derive_counter_pda(mint, user).0,
+                system_program: SYSTEM_PROGRAM_ID,
             }
                 .to_account_metas(None),
-            data: crate::instruction::AddToWhitelist {}.data(),
+            data: crate::instruction::MigrateWhitelistEntry {}.data(),
         };
-        let bh = program.latest_blockhash();
-        let tx_add =
-            Transaction::new_signed_with_payer(&[ix_add], Some(&payer.pubkey()), &[&payer], bh);
-        program.send_transaction(tx_add).unwrap();
+        send(program, &[admin], &[ix])
+    }
 
-        // Remove from whitelist
-        let ix_remove = Instruction {
+    fn close_legacy(program: &mut LiteSVM, authority: &Keypair, user: &Pubkey) -> Result<(), String> {
+        let ix = Instruction {
             program_id: PROGRAM_ID,
-            accounts: crate::accounts::RemoveFromWhitelist {
-                whitelist: whitelist_pda,
-                authority: payer.pubkey(),
+            accounts: crate::accounts::CloseLegacyWhitelistEntry {
+                authority: authority.pubkey(),
+                program: PROGRAM_ID,
+                program_data: derive_program_data(),
+                legacy_whitelist: derive_legacy_whitelist_pda(user).0,
+                user: *user,
             }
                 .to_account_metas(None),
-            data: crate::instruction::RemoveFromWhitelist {}.data(),
+            data: crate::instruction::CloseLegacyWhitelistEntry {}.data(),
         };
-        let bh = program.latest_blockhash();
-        let tx_remove =
-            Transaction::new_signed_with_payer(&[ix_remove], Some(&payer.pubkey()), &[&payer], bh);
-        program.send_transaction(tx_remove).expect("Remove failed");
+        send(program, &[authority], &[ix])
+    }
+
+    #[test]
+    fn test_migrate_whitelist_entry() {
+        let (mut program, payer) = setup();
+        let mint_x = setup_mint(&mut program, &payer);
+        let mint_y = setup_mint(&mut program, &payer);
+        let stranger = funded_keypair(&mut program);
+
+        // From before roles, and from after
+        let old_user = Pubkey::new_unique();
+        let sender = Pubkey::new_unique();
+        let legacy = set_legacy_entry(&mut program, &old_user, None);
+        set_legacy_entry(&mut program, &sender, Some(Role::SendOnly));
+
+        let result = migrate(&mut program, &stranger, &mint_x, &old_user);
+        assert_hook_error(result, HookError::Unauthorized);
+
+        migrate(&mut program, &payer, &mint_x, &old_user).expect("Migration to X failed");
+        let entry = read_whitelist(&program, &mint_x, &old_user).unwrap();
+        assert_eq!((entry.mint, entry.user, entry.role), (mint_x, old_user, Role::Both));
+
+        // Only once per mint
+        let result = migrate(&mut program, &payer, &mint_x, &old_user);
+        assert!(result.is_err(), "Second migration to the same mint should fail");
+
+        // The global entry stays for the other mints
+        migrate(&mut program, &payer, &mint_y, &old_user).expect("Migration to Y failed");
+        assert!(read_whitelist(&program, &mint_y, &old_user).is_some());
+        assert!(program.get_account(&legacy).is_some_and(|account| account.lamports > 0));
+
+        migrate(&mut program, &payer, &mint_x, &sender).expect("Migration failed");
+        assert_eq!(read_whitelist(&program, &mint_x, &sender).unwrap().role, Role::SendOnly);
+    }
+
+    #[test]
+    fn test_close_legacy_whitelist_entry() {
+        let (mut program, payer) = setup();
+        let upgrade_authority = funded_keypair(&mut program);
+        set_upgrade_authority(&mut program, &upgrade_authority.pubkey());
+
+        let mint = setup_mint(&mut program, &payer);
+        let user = Pubkey::new_unique();
+        let legacy = set_legacy_entry(&mut program, &user, Some(Role::Both));
+        migrate(&mut program, &payer, &mint, &user).expect("Migration failed");
+
+        // Anyone can become some mint's whitelist admin, so that's not enough
+        let result = close_legacy(&mut program, &payer, &user);
+        assert_hook_error(result, HookError::NotUpgradeAuthority);
+
+        let balance = program.get_balance(&upgrade_authority.pubkey()).unwrap();
+        close_legacy(&mut program, &upgrade_authority, &user).expect("Close failed");
+        assert!(program.get_account(&legacy).map_or(true, |account| account.lamports == 0));
+        assert!(program.get_balance(&upgrade_authority.pubkey()).unwrap() > balance);
+
+        // The migrated entry is untouched
+        assert_eq!(read_whitelist(&program, &mint, &user).unwrap().role, Role::Both);
+    }
+
+    /// Plants the one-entry list created before entries were scoped to a mint
+    fn set_legacy_extra_account_meta_list(program: &mut LiteSVM, mint: &Pubkey) -> Pubkey {
+        let account_metas = [ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal { bytes: b"hook".to_vec() }, Seed::AccountKey { index: 3 }],
+            false,
+            false,
+        ).unwrap()];
+        let mut data = vec![0; ExtraAccountMetaList::size_of(account_metas.len()).unwrap()];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &account_metas).unwrap();
 
-        // PDA should now fail to deserialize (simulates account closure)
-        let whitelist_account = program.get_account(&whitelist_pda).expect("Whitelist PDA not found");
-        let result = WhitelistEntry::try_deserialize(&mut whitelist_account.data.as_slice());
-        assert!(result.is_err(), "Whitelist PDA should be closed");
+        let list = derive_extra_account_meta_list(mint);
+        program.set_account(list, Account {
+            lamports: program.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+        list
+    }
+
+    fn update_extra_account_meta_list(program: &mut LiteSVM, authority: &Keypair, mint: &Pubkey) -> Result<(), String> {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::UpdateExtraAccountMetaList {
+                authority: authority.pubkey(),
+                extra_account_meta_list: derive_extra_account_meta_list(mint),
+                mint: *mint,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+                .to_account_metas(None),
+            data: crate::instruction::UpdateExtraAccountMetaList {}.data(),
+        };
+        send(program, &[authority], &[ix])
+    }
+
+    #[test]
+    fn test_update_extra_account_meta_list() {
+        let (mut program, payer) = setup();
+        let mint = create_mint(&mut program, &payer);
+        initialize_config(&mut program, &payer, &mint, &payer.pubkey()).expect("Config init failed");
+        let list = set_legacy_extra_account_meta_list(&mut program, &mint);
+
+        let sender = funded_keypair(&mut program);
+        let receiver = funded_keypair(&mut program);
+        for user in [&sender, &receiver] {
+            add_to_whitelist(&mut program, &payer, &mint, &user.pubkey(), Role::Both).unwrap();
+        }
+        let sender_tokens = create_token_account(&mut program, &payer, &mint, &sender.pubkey(), 100);
+        let receiver_tokens = create_token_account(&mut program, &payer, &mint, &receiver.pubkey(), 0);
+
+        // The old list resolves the global entry instead of the mint's
+        let result = transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 10);
+        assert!(result.is_err(), "Transfer with the old list should fail");
+
+        let stranger = funded_keypair(&mut program);
+        let result = update_extra_account_meta_list(&mut program, &stranger, &mint);
+        assert_hook_error(result, HookError::NotHookAuthority);
+
+        update_extra_account_meta_list(&mut program, &payer, &mint).expect("Update failed");
+        let account = program.get_account(&list).unwrap();
+        assert_eq!(account.data.len(), ExtraAccountMetaList::size_of(3).unwrap());
+        assert!(account.lamports >= program.minimum_balance_for_rent_exemption(account.data.len()));
+
+        transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 10)
+            .expect("Transfer with the updated list failed");
+    }
+
+    /// Whitelists a sender and a receiver of a fresh mint, returning their
+    /// token accounts
+    fn setup_transfer(program: &mut LiteSVM, payer: &Keypair) -> (Pubkey, Keypair, Pubkey, Keypair, Pubkey) {
+        let mint = setup_mint(program, payer);
+        let sender = funded_keypair(program);
+        let receiver = funded_keypair(program);
+        for user in [&sender, &receiver] {
+            add_to_whitelist(program, payer, &mint, &user.pubkey(), Role::Both).unwrap();
+        }
+
+        let sender_tokens = create_token_account(program, payer, &mint, &sender.pubkey(), 1_000);
+        let receiver_tokens = create_token_account(program, payer, &mint, &receiver.pubkey(), 0);
+        (mint, sender, sender_tokens, receiver, receiver_tokens)
+    }
+
+    #[test]
+    fn test_set_limits() {
+        let (mut program, payer) = setup();
+        let mint = setup_mint(&mut program, &payer);
+        let stranger = funded_keypair(&mut program);
+        let user = Pubkey::new_unique();
+        add_to_whitelist(&mut program, &payer, &mint, &user, Role::Both).unwrap();
+
+        let result = set_limits(&mut program, &stranger, &mint, &user, 10, 100);
+        assert_hook_error(result, HookError::Unauthorized);
 
-        println!("Removed user {} and closed PDA successfully!", user.pubkey());
+        set_limits(&mut program, &payer, &mint, &user, 10, 100).expect("Set limits failed");
+        let entry = read_whitelist(&program, &mint, &user).unwrap();
+        assert_eq!((entry.max_per_transfer, entry.daily_limit), (10, 100));
     }
 
+    #[test]
+    fn test_transfer_exceeds_max_per_transfer() {
+        let (mut program, payer) = setup();
+        let (mint, sender, sender_tokens, receiver, receiver_tokens) = setup_transfer(&mut program, &payer);
+        set_limits(&mut program, &payer, &mint, &sender.pubkey(), 50, 0).unwrap();
+
+        let result = transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 51);
+        assert_hook_error(result, HookError::TransferLimitExceeded);
+
+        // Right at the cap is fine, and with no daily limit so is doing it again
+        for _ in 0..3 {
+            transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 50)
+                .expect("Transfer at the cap failed");
+        }
+        assert_eq!(read_counter(&program, &mint, &sender.pubkey()).unwrap().volume(), 150);
+    }
+
+    #[test]
+    fn test_transfer_exceeds_daily_limit() {
+        let (mut program, payer) = setup();
+        let (mint, sender, sender_tokens, receiver, receiver_tokens) = setup_transfer(&mut program, &payer);
+        set_limits(&mut program, &payer, &mint, &sender.pubkey(), 0, 100).unwrap();
+
+        // Start of an hour
+        let start = 472_223 * VOLUME_BUCKET_SECONDS;
+        set_clock(&mut program, start);
+        transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 10)
+            .expect("First transfer failed");
+
+        // The last second of the day still counts the first transfer
+        set_clock(&mut program, start + VOLUME_WINDOW_SECONDS - 1);
+        let result = transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 91);
+        assert_hook_error(result, HookError::DailyLimitExceeded);
+        transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 90)
+            .expect("Transfer up to the limit failed");
+        assert_eq!(read_counter(&program, &mint, &sender.pubkey()).unwrap().volume(), 100);
+
+        // A second later only the first transfer has left the window, so the
+        // limit can't be spent twice around the boundary
+        set_clock(&mut program, start + VOLUME_WINDOW_SECONDS);
+        let result = transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 11);
+        assert_hook_error(result, HookError::DailyLimitExceeded);
+        transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 10)
+            .expect("Transfer of what left the window failed");
+
+        // The 90 leaves the window a day after its hour started
+        set_clock(&mut program, start + 2 * VOLUME_WINDOW_SECONDS - VOLUME_BUCKET_SECONDS - 1);
+        let result = transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 1);
+        assert_hook_error(result, HookError::DailyLimitExceeded);
+        set_clock(&mut program, start + 2 * VOLUME_WINDOW_SECONDS - VOLUME_BUCKET_SECONDS);
+        transfer(&mut program, &sender, &mint, &sender_tokens, &receiver_tokens, &receiver.pubkey(), 90)
+            .expect("Transfer after the 90 left the window failed");
+        assert_eq!(read_counter(&program, &mint, &sender.pubkey()).unwrap().volume(), 100);
+
+        // Receiving doesn't count towards the receiver's volume
+        assert_eq!(read_counter(&program, &mint, &receiver.pubkey()).unwrap().volume(), 0);
+    }
+
+    #[test]
+    fn test_transfer_hook_rejects_direct_calls() {
+        let (mut program, payer) = setup();
+        let (mint, sender, sender_tokens, receiver, receiver_tokens) = setup_transfer(&mut program, &payer);
+
+        // Calling the hook outside of a transfer would run up the sender's volume
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::TransferHook {
+                source_token: sender_tokens,
+                mint,
+                destination_token: receiver_tokens,
+                owner: sender.pubkey(),
+                extra_account_meta_list: derive_extra_account_meta_list(&mint),
+                source_whitelist: derive_whitelist_pda(&mint, &sender.pubkey()).0,
+                destination_whitelist: derive_whitelist_pda(&mint, &receiver.pubkey()).0,
+                counter: derive_counter_pda(&mint, &sender.pubkey()).0,
+            }
+                .to_account_metas(None),
+            data: crate::instruction::TransferHook { amount: 10 }.data(),
+        };
+        let result = send(&mut program, &[&payer], &[ix]);
+        assert_hook_error(result, HookError::NotTransferring);
+        assert_eq!(read_counter(&program, &mint, &sender.pubkey()).unwrap().volume(), 0);
+    }
 }
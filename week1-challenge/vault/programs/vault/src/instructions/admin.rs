@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::utils::transfer_hook_program_id;
+use crate::{Vault, VaultError, MAX_BPS};
+
+#[derive(Accounts)]
+pub struct VaultAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = admin @ VaultError::Unauthorized,
+    )]
+    pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        constraint = vault.pending_admin == Some(new_admin.key()) @ VaultError::Unauthorized,
+    )]
+    pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct SetEarlyWithdrawPenalty<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = admin @ VaultError::Unauthorized,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(constraint = treasury_token_account.mint == vault.mint @ VaultError::InvalidTreasury)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SyncHookProgram<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = admin @ VaultError::Unauthorized,
+        has_one = mint,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+/// Stops deposits and withdrawals, leaving only `emergency_withdraw`
+pub fn pause_handler(ctx: Context<VaultAdmin>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    require!(!vault.paused, VaultError::VaultPaused);
+    vault.paused = true;
+
+    msg!("Vault for mint {} paused", vault.mint);
+    Ok(())
+}
+
+pub fn unpause_handler(ctx: Context<VaultAdmin>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    require!(vault.paused, VaultError::VaultNotPaused);
+    vault.paused = false;
+
+    msg!("Vault for mint {} unpaused", vault.mint);
+    Ok(())
+}
+
+/// Offers the role to `new_admin`, who has to take it with `accept_admin`.
+/// The current admin keeps it until then, and can offer it to someone else
+pub fn transfer_admin_handler(ctx: Context<VaultAdmin>, new_admin: Pubkey) -> Result<()> {
+    require!(new_admin != Pubkey::default(), VaultError::InvalidAdmin);
+    ctx.accounts.vault.pending_admin = Some(new_admin);
+
+    msg!("Vault admin role offered to {}", new_admin);
+    Ok(())
+}
+
+pub fn accept_admin_handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.admin = ctx.accounts.new_admin.key();
+    vault.pending_admin = None;
+
+    msg!("Vault admin is now {}", vault.admin);
+    Ok(())
+}
+
+/// `limit` is in tokens per day, 0 turns the limit off
+pub fn set_withdraw_limit_handler(ctx: Context<VaultAdmin>, limit: u64) -> Result<()> {
+    ctx.accounts.vault.withdraw_limit = limit;
+
+    msg!("Vault withdraw limit set to {} per day", limit);
+    Ok(())
+}
+
+/// Penalties for withdrawing locked positions go to `treasury_token_account`.
+/// Positions only take the new terms on their next deposit
+pub fn set_early_withdraw_penalty_handler(ctx: Context<SetEarlyWithdrawPenalty>, penalty_bps: u16) -> Result<()> {
+    require!(penalty_bps <= MAX_BPS, VaultError::InvalidPenalty);
+
+    let vault = &mut ctx.accounts.vault;
+    vault.early_withdraw_penalty_bps = penalty_bps;
+    vault.treasury = ctx.accounts.treasury_token_account.key();
+
+    msg!("Early withdraw penalty set to {} bps, paid to {}", penalty_bps, vault.treasury);
+    Ok(())
+}
+
+/// Accepts the hook program the mint points at now, after its hook authority
+/// re-pointed it
+pub fn sync_hook_program_handler(ctx: Context<SyncHookProgram>) -> Result<()> {
+    let hook_program = transfer_hook_program_id(&ctx.accounts.mint.to_account_info())?;
+    ctx.accounts.vault.hook_program = hook_program;
+
+    msg!("Vault hook program is now {:?}", hook_program);
+    Ok(())
+}
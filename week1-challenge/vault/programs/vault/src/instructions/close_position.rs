@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::{UserPosition, Vault, VaultError};
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"position", vault.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.shares == 0 @ VaultError::PositionNotEmpty,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+}
+
+pub fn close_position_handler(ctx: Context<ClosePosition>) -> Result<()> {
+    msg!("Closed position of user {}", ctx.accounts.user.key());
+    Ok(())
+}
@@ -1,11 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke;
 use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
     associated_token::AssociatedToken,
 };
-use spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi;
-use crate::Vault;
+use crate::utils::{require_hook_program, transfer_checked_with_hook};
+use crate::{shares, UserPosition, Vault};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
@@ -17,11 +16,21 @@ pub struct Deposit<'info> {
 
     #[account(
         mut,
-        seeds = [b"vault"],
+        seeds = [b"vault", vault.mint.as_ref()],
         bump = vault.bump,
+        constraint = !vault.paused @ VaultError::VaultPaused,
     )]
     pub vault: Account<'info, Vault>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<UserPosition>(),
+        seeds = [b"position", vault.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
     #[account(
         init_if_needed,
         payer = user,
@@ -34,78 +43,76 @@ pub struct Deposit<'info> {
     #[account(mut, address = vault.mint)]
     pub mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: Transfer hook program
-    pub hook_program: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"extra-account-metas", mint.key().as_ref()],
-        bump,
-        seeds::program = hook_program.key(),
-    )]
-    /// CHECK: ExtraAccountMetaList PDA
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"hook", user.key().as_ref()],
-        bump,
-        seeds::program = hook_program.key(),
-    )]
-    /// CHECK: User whitelist PDA
-    pub user_whitelist: UncheckedAccount<'info>,
-
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn deposit_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+/// Locks the whole position for at least `lock_duration` seconds from now, an
+/// existing later unlock time is kept. The whole position takes the vault's
+/// current early withdraw penalty and treasury.
+/// `remaining_accounts` carries the transfer hook's extra accounts, if the mint has a hook
+pub fn deposit_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+    amount: u64,
+    lock_duration: i64,
+) -> Result<()> {
     require!(amount > 0, VaultError::InvalidAmount);
+    require!(lock_duration >= 0, VaultError::InvalidLockDuration);
 
-    // Build the base transfer_checked instruction
-    let mut transfer_ix = spl_token_2022::instruction::transfer_checked(
-        ctx.accounts.token_program.key,
-        &ctx.accounts.user_token_account.key(),
-        &ctx.accounts.mint.key(),
-        &ctx.accounts.vault_token_account.key(),
-        ctx.accounts.user.key,
-        &[],
+    let now = Clock::get()?.unix_timestamp;
+    let unlock_time = now
+        .checked_add(lock_duration)
+        .ok_or(VaultError::Overflow)?;
+
+    // Price the deposit against the UI amount the vault held before it
+    let ui_scale = shares::ui_scale(&ctx.accounts.mint.to_account_info(), now)?;
+    let shares = shares::shares_for_deposit(
         amount,
-        ctx.accounts.mint.decimals,
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
+        ui_scale,
     )?;
+    require!(shares > 0, VaultError::ZeroShares);
 
-    // Start with base account infos for transfer
-    let mut cpi_account_infos = vec![
+    require_hook_program(&ctx.accounts.mint.to_account_info(), ctx.accounts.vault.hook_program)?;
+    transfer_checked_with_hook(
+        &ctx.accounts.token_program,
         ctx.accounts.user_token_account.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
-        ctx.accounts.vault_token_account.to_account_info(),
-        ctx.accounts.user.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-    ];
-
-    // IMPORTANT: Order MATTERSSSSS
-    let additional_accounts = [
-        ctx.accounts.hook_program.to_account_info(),           // Hook program FIRST
-        ctx.accounts.extra_account_meta_list.to_account_info(), // Then the list itself
-        ctx.accounts.user_whitelist.to_account_info(),          // Finally the whitelist
-    ];
-
-    // Add extra accounts for transfer hook CPI
-    add_extra_accounts_for_execute_cpi(
-        &mut transfer_ix,
-        &mut cpi_account_infos,
-        &ctx.accounts.hook_program.key(),
-        ctx.accounts.user_token_account.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.mint,
         ctx.accounts.vault_token_account.to_account_info(),
         ctx.accounts.user.to_account_info(),
+        ctx.remaining_accounts,
         amount,
-        &additional_accounts,
+        &[],
     )?;
 
-    // Execute the CPI with all resolved accounts
-    invoke(&transfer_ix, &cpi_account_infos)?;
-
-    msg!("Deposited {} tokens to vault", amount);
+    // Credit the user's position with the new shares
+    let position = &mut ctx.accounts.user_position;
+    position.vault = ctx.accounts.vault.key();
+    position.user = ctx.accounts.user.key();
+    position.bump = ctx.bumps.user_position;
+    position.shares = position.shares
+        .checked_add(shares)
+        .ok_or(VaultError::Overflow)?;
+    position.unlock_time = position.unlock_time.max(unlock_time);
+    position.early_withdraw_penalty_bps = ctx.accounts.vault.early_withdraw_penalty_bps;
+    position.treasury = ctx.accounts.vault.treasury;
+
+    let vault = &mut ctx.accounts.vault;
+    vault.total_shares = vault.total_shares
+        .checked_add(shares)
+        .ok_or(VaultError::Overflow)?;
+    vault.total_assets = vault.total_assets
+        .checked_add(amount)
+        .ok_or(VaultError::Overflow)?;
+
+    msg!(
+        "Deposited {} tokens ({} UI units) to vault for {} shares",
+        amount,
+        (amount as f64 * ui_scale) as u64,
+        shares
+    );
 
     Ok(())
 }
@@ -114,4 +121,42 @@ pub fn deposit_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
 pub enum VaultError {
     #[msg("Amount must be greater than zero")]
     InvalidAmount,
+    #[msg("Amount exceeds the user's shares")]
+    InsufficientBalance,
+    #[msg("Position still holds a balance")]
+    PositionNotEmpty,
+    #[msg("Position balance overflow")]
+    Overflow,
+    #[msg("Amount is too small to be worth a share")]
+    ZeroShares,
+    #[msg("Remaining accounts don't include the mint's transfer hook program")]
+    HookProgramMismatch,
+    #[msg("Remaining accounts don't include the hook's ExtraAccountMetaList")]
+    MissingExtraAccountMetas,
+    #[msg("Only the vault admin can do this")]
+    Unauthorized,
+    #[msg("Vault is paused")]
+    VaultPaused,
+    #[msg("Vault is not paused")]
+    VaultNotPaused,
+    #[msg("Withdrawal exceeds what's left of the daily limit")]
+    WithdrawLimitExceeded,
+    #[msg("Lock duration can't be negative")]
+    InvalidLockDuration,
+    #[msg("Position is still locked")]
+    PositionLocked,
+    #[msg("New unlock time must be later than the current one")]
+    LockNotExtended,
+    #[msg("Penalty can't exceed 100%")]
+    InvalidPenalty,
+    #[msg("Treasury token account doesn't match the vault's")]
+    InvalidTreasury,
+    #[msg("Mint's transfer hook authority could change the hook")]
+    MutableTransferHook,
+    #[msg("Mint's transfer hook program changed since the vault was set up")]
+    HookProgramChanged,
+    #[msg("Only the mint's transfer hook authority or the program's upgrade authority can set up its vault")]
+    NotVaultCreator,
+    #[msg("Admin can't be the default pubkey")]
+    InvalidAdmin,
 }
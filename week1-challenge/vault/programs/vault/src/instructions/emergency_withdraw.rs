@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::utils::{require_hook_program, transfer_checked_with_hook};
+use crate::{shares, UserPosition, Vault, VaultError};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = admin @ VaultError::Unauthorized,
+        constraint = vault.paused @ VaultError::VaultNotPaused,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), user_position.user.as_ref()],
+        bump = user_position.bump,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    /// Funds only ever go back to the position's owner
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == user_position.user,
+        constraint = owner_token_account.mint == mint.key(),
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = vault.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// While the vault is paused, the admin can return a user's whole position to
+/// them. This skips the withdrawal limit.
+/// `remaining_accounts` carries the transfer hook's extra accounts, if the mint has a hook
+pub fn emergency_withdraw_handler<'info>(ctx: Context<'_, '_, '_, 'info, EmergencyWithdraw<'info>>) -> Result<()> {
+    let position = &mut ctx.accounts.user_position;
+    let shares = position.shares;
+    require!(shares > 0, VaultError::InvalidAmount);
+
+    let amount = shares::amount_for_shares(
+        shares,
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
+    )?;
+
+    position.shares = 0;
+    ctx.accounts.vault.total_shares -= shares;
+    ctx.accounts.vault.total_assets -= amount;
+
+    require_hook_program(&ctx.accounts.mint.to_account_info(), ctx.accounts.vault.hook_program)?;
+
+    let vault_seeds = &[b"vault".as_ref(), ctx.accounts.vault.mint.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer_seeds = &[&vault_seeds[..]];
+
+    transfer_checked_with_hook(
+        &ctx.accounts.token_program,
+        ctx.accounts.vault_token_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.owner_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    msg!("Returned {} tokens for {} shares to {}", amount, shares, ctx.accounts.user_position.user);
+
+    Ok(())
+}
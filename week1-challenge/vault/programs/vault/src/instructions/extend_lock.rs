@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::{UserPosition, Vault, VaultError};
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+    )]
+    pub user_position: Account<'info, UserPosition>,
+}
+
+/// Moves the position's unlock time to `lock_duration` seconds from now, which
+/// has to be later than it already is
+pub fn extend_lock_handler(ctx: Context<ExtendLock>, lock_duration: i64) -> Result<()> {
+    let unlock_time = Clock::get()?
+        .unix_timestamp
+        .checked_add(lock_duration)
+        .ok_or(VaultError::Overflow)?;
+
+    let position = &mut ctx.accounts.user_position;
+    require!(unlock_time > position.unlock_time, VaultError::LockNotExtended);
+    position.unlock_time = unlock_time;
+
+    msg!("Position of user {} locked until {}", position.user, unlock_time);
+    Ok(())
+}
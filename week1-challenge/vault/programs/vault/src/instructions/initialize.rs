@@ -1,38 +1,62 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenInterface};
-use crate::Vault;
+use crate::utils::transfer_hook_extension;
+use crate::{Vault, VaultError};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// The mint must already exist, with or without a transfer hook. Its mint
+    /// authority plays no part, so fixed-supply, multisig and PDA mints work too
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// One vault per mint
     #[account(
         init,
         payer = user,
         space = 8 + std::mem::size_of::<Vault>(),
-        seeds = [b"vault"],
+        seeds = [b"vault", mint.key().as_ref()],
         bump
     )]
     pub vault: Account<'info, Vault>,
 
-    /// The mint must already exist, created off-chain with a transfer hook attached
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    /// CHECK: Transfer hook program (We don't really need it here)
-    pub hook_program: UncheckedAccount<'info>,
-
     /// It will support both
     pub token_program: Interface<'info, TokenInterface>,
 
     pub system_program: Program<'info, System>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ VaultError::NotVaultCreator)]
+    pub program: Program<'info, crate::program::Vault>,
+
+    /// Its upgrade authority can set up a vault for any mint
+    pub program_data: Account<'info, ProgramData>,
 }
 
-pub fn initialize_handler(ctx: Context<Initialize>) -> Result<()> {
+/// Records the mint's transfer hook program, which deposits and withdrawals
+/// then hold it to. Only the mint's transfer hook authority or the program's
+/// upgrade authority can set up the vault, and with it become its admin. A
+/// mint whose hook authority could re-point the hook is only accepted with
+/// `allow_mutable_hook`, which nobody else can pass.
+pub fn initialize_handler(ctx: Context<Initialize>, allow_mutable_hook: bool) -> Result<()> {
+    let hook = transfer_hook_extension(&ctx.accounts.mint.to_account_info())?;
+    let hook_program = hook.and_then(|hook| Option::<Pubkey>::from(hook.program_id));
+    let hook_authority = hook.and_then(|hook| Option::<Pubkey>::from(hook.authority));
+
+    let user = ctx.accounts.user.key();
+    require!(
+        hook_authority == Some(user) || ctx.accounts.program_data.upgrade_authority_address == Some(user),
+        VaultError::NotVaultCreator
+    );
+    require!(hook_authority.is_none() || allow_mutable_hook, VaultError::MutableTransferHook);
+
     let vault = &mut ctx.accounts.vault;
     vault.bump = ctx.bumps.vault;
     vault.mint = ctx.accounts.mint.key();
+    vault.admin = ctx.accounts.user.key();
+    vault.hook_program = hook_program;
 
-    msg!("Vault initialized for mint with hook: {}", ctx.accounts.hook_program.key());
+    msg!("Vault initialized for mint with hook: {:?}", hook_program);
     Ok(())
 }
@@ -1,7 +1,15 @@
 pub mod initialize;
 pub mod deposit;
 pub mod withdraw;
+pub mod close_position;
+pub mod admin;
+pub mod emergency_withdraw;
+pub mod extend_lock;
 
 pub use initialize::*;
 pub use deposit::*;
-pub use withdraw::*;
\ No newline at end of file
+pub use withdraw::*;
+pub use close_position::*;
+pub use admin::*;
+pub use emergency_withdraw::*;
+pub use extend_lock::*;
\ No newline at end of file
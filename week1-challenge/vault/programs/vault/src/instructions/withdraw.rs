@@ -1,10 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke_signed;
 use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
-use spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi;
-use crate::Vault;
+use crate::utils::{require_hook_program, transfer_checked_with_hook};
+use crate::{shares, UserPosition, Vault, VaultError};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -20,94 +19,111 @@ pub struct Withdraw<'info> {
 
     #[account(
         mut,
-        seeds = [b"vault"],
+        seeds = [b"vault", vault.mint.as_ref()],
         bump = vault.bump,
+        constraint = !vault.paused @ VaultError::VaultPaused,
     )]
     pub vault: Account<'info, Vault>,
 
     #[account(
         mut,
-        constraint = vault_token_account.owner == vault.key(),
-        constraint = vault_token_account.mint == mint.key(),
+        seeds = [b"position", vault.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
     )]
-    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub mint: InterfaceAccount<'info, Mint>,
-
-    /// CHECK: Transfer hook program
-    pub hook_program: UncheckedAccount<'info>,
+    pub user_position: Account<'info, UserPosition>,
 
     #[account(
-        seeds = [b"extra-account-metas", mint.key().as_ref()],
-        bump,
-        seeds::program = hook_program.key(),
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
     )]
-    /// CHECK: ExtraAccountMetaList PDA
-    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(
-        seeds = [b"hook", vault.key().as_ref()],
-        bump,
-        seeds::program = hook_program.key(),
-    )]
-    /// CHECK: Vault whitelist PDA
-    pub vault_whitelist: UncheckedAccount<'info>,
+    /// Only needed when withdrawing before the position unlocks
+    #[account(mut, address = user_position.treasury @ VaultError::InvalidTreasury)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, address = vault.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn withdraw_handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-    require!(amount > 0, WithdrawError::InvalidAmount);
-
-    // Build the base transfer_checked instruction
-    let mut transfer_ix = spl_token_2022::instruction::transfer_checked(
-        ctx.accounts.token_program.key,
-        &ctx.accounts.vault_token_account.key(),
-        &ctx.accounts.mint.key(),
-        &ctx.accounts.user_token_account.key(),
-        &ctx.accounts.vault.key(),
-        &[],
-        amount,
-        ctx.accounts.mint.decimals,
+/// Burns `shares` from the user's position for their proportion of the vault.
+/// Before the position unlocks this only goes through with `accept_penalty`,
+/// and the penalty the position was deposited under goes to its treasury. A
+/// position deposited without a penalty has to wait for the unlock.
+/// `remaining_accounts` carries the transfer hook's extra accounts, if the mint has a hook
+pub fn withdraw_handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+    shares: u64,
+    accept_penalty: bool,
+) -> Result<()> {
+    require!(shares > 0, WithdrawError::InvalidAmount);
+
+    // Users can only burn the shares they hold
+    let position = &mut ctx.accounts.user_position;
+    require!(position.shares >= shares, VaultError::InsufficientBalance);
+
+    let amount = shares::amount_for_shares(
+        shares,
+        ctx.accounts.vault.total_assets,
+        ctx.accounts.vault.total_shares,
     )?;
+    require!(amount > 0, VaultError::ZeroShares);
 
-    // Start with base account infos
-    let mut cpi_account_infos = vec![
-        ctx.accounts.vault_token_account.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
-        ctx.accounts.user_token_account.to_account_info(),
-        ctx.accounts.vault.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-    ];
-
-    // Additional accounts for the hook
-    let additional_accounts = [
-        ctx.accounts.hook_program.to_account_info(),
-        ctx.accounts.extra_account_meta_list.to_account_info(),
-        ctx.accounts.vault_whitelist.to_account_info(),
-    ];
-
-    // Add extra accounts for transfer hook CPI
-    add_extra_accounts_for_execute_cpi(
-        &mut transfer_ix,
-        &mut cpi_account_infos,
-        &ctx.accounts.hook_program.key(),
+    let now = Clock::get()?.unix_timestamp;
+    let penalty = if now < position.unlock_time {
+        require!(
+            accept_penalty && position.early_withdraw_penalty_bps > 0,
+            VaultError::PositionLocked
+        );
+        position.early_withdraw_penalty(amount)?
+    } else {
+        0
+    };
+
+    ctx.accounts.vault.record_withdrawal(amount, now)?;
+
+    position.shares -= shares;
+    ctx.accounts.vault.total_shares -= shares;
+    ctx.accounts.vault.total_assets -= amount;
+
+    require_hook_program(&ctx.accounts.mint.to_account_info(), ctx.accounts.vault.hook_program)?;
+
+    let vault_seeds = &[b"vault".as_ref(), ctx.accounts.vault.mint.as_ref(), &[ctx.accounts.vault.bump]];
+    let signer_seeds = &[&vault_seeds[..]];
+
+    if penalty > 0 {
+        let treasury = ctx.accounts.treasury_token_account
+            .as_ref()
+            .ok_or(VaultError::InvalidTreasury)?;
+
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.vault_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            treasury.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.remaining_accounts,
+            penalty,
+            signer_seeds,
+        )?;
+    }
+
+    transfer_checked_with_hook(
+        &ctx.accounts.token_program,
         ctx.accounts.vault_token_account.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.mint,
         ctx.accounts.user_token_account.to_account_info(),
         ctx.accounts.vault.to_account_info(),
-        amount,
-        &additional_accounts,
+        ctx.remaining_accounts,
+        amount - penalty,
+        signer_seeds,
     )?;
 
-    // Execute the CPI with PDA signer
-    let vault_seeds = &[b"vault".as_ref(), &[ctx.accounts.vault.bump]];
-    let signer_seeds = &[&vault_seeds[..]];
-
-    invoke_signed(&transfer_ix, &cpi_account_infos, signer_seeds)?;
-
-    msg!("Withdrew {} tokens from vault to user", amount);
+    msg!("Burned {} shares for {} tokens from vault to user, {} of it as penalty", shares, amount, penalty);
 
     Ok(())
 }
@@ -1,6 +1,8 @@
 pub mod constants;
 pub mod error;
 pub mod instructions;
+pub mod shares;
+pub mod utils;
 pub mod state;
 
 mod tests;
@@ -17,18 +19,76 @@ declare_id!("6uyR4Zo5spqveu1yxXQUQ5HSM1a25WfVi89HW96MLKsR");
 pub mod vault {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        initialize::initialize_handler(ctx)?;
+    pub fn initialize(ctx: Context<Initialize>, allow_mutable_hook: bool) -> Result<()> {
+        initialize::initialize_handler(ctx, allow_mutable_hook)?;
         Ok(())
     }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        deposit_handler(ctx, amount)?;
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+        amount: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        deposit_handler(ctx, amount, lock_duration)?;
         Ok(())
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        withdraw_handler(ctx, amount)?;
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        shares: u64,
+        accept_penalty: bool,
+    ) -> Result<()> {
+        withdraw_handler(ctx, shares, accept_penalty)?;
+        Ok(())
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        close_position_handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<VaultAdmin>) -> Result<()> {
+        pause_handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<VaultAdmin>) -> Result<()> {
+        unpause_handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn transfer_admin(ctx: Context<VaultAdmin>, new_admin: Pubkey) -> Result<()> {
+        transfer_admin_handler(ctx, new_admin)?;
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        accept_admin_handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_withdraw_limit(ctx: Context<VaultAdmin>, limit: u64) -> Result<()> {
+        set_withdraw_limit_handler(ctx, limit)?;
+        Ok(())
+    }
+
+    pub fn emergency_withdraw<'info>(ctx: Context<'_, '_, '_, 'info, EmergencyWithdraw<'info>>) -> Result<()> {
+        emergency_withdraw_handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn set_early_withdraw_penalty(ctx: Context<SetEarlyWithdrawPenalty>, penalty_bps: u16) -> Result<()> {
+        set_early_withdraw_penalty_handler(ctx, penalty_bps)?;
+        Ok(())
+    }
+
+    pub fn sync_hook_program(ctx: Context<SyncHookProgram>) -> Result<()> {
+        sync_hook_program_handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn extend_lock(ctx: Context<ExtendLock>, lock_duration: i64) -> Result<()> {
+        extend_lock_handler(ctx, lock_duration)?;
         Ok(())
     }
 }
@@ -0,0 +1,101 @@
+//! Share accounting for the vault.
+//!
+//! Deposits mint shares priced against the UI amount of the underlying held by
+//! the vault at the current `Clock`, and withdrawals burn shares for the same
+//! proportion of it. The first deposit mints one share per UI unit, so a share
+//! starts out worth one UI unit and grows in value as an interest-bearing mint
+//! accrues (or a scaled UI amount mint rebases). A later deposit of the same UI
+//! amount buys fewer shares, and interest belongs to whoever held shares while
+//! it accrued.
+//!
+//! The underlying is the vault's `total_assets` tally, not the balance of its
+//! token account. Anyone can send tokens to that account, and counting them
+//! would let the first depositor inflate the share price until later deposits
+//! round down to nothing.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        interest_bearing_mint::InterestBearingConfig,
+        scaled_ui_amount::ScaledUiAmountConfig,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+};
+use crate::VaultError;
+
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+/// Factor turning a raw amount of `mint` into its UI amount (before decimals)
+/// at `unix_timestamp`. `1.0` for mints without a scaling extension.
+pub fn ui_scale(mint: &AccountInfo, unix_timestamp: i64) -> Result<f64> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(1.0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    if let Ok(config) = mint_state.get_extension::<InterestBearingConfig>() {
+        let initialized_at = i64::from(config.initialization_timestamp);
+        let last_update = i64::from(config.last_update_timestamp);
+        let pre_update_rate = i16::from(config.pre_update_average_rate) as f64;
+        let current_rate = i16::from(config.current_rate) as f64;
+
+        // Continuous compounding, as Token-2022 computes it
+        let pre_update_years = (last_update - initialized_at) as f64 / SECONDS_PER_YEAR;
+        let post_update_years = (unix_timestamp - last_update) as f64 / SECONDS_PER_YEAR;
+        let pre_update_exp = (pre_update_rate / ONE_IN_BASIS_POINTS * pre_update_years).exp();
+        let post_update_exp = (current_rate / ONE_IN_BASIS_POINTS * post_update_years).exp();
+
+        return Ok(pre_update_exp * post_update_exp);
+    }
+
+    if let Ok(config) = mint_state.get_extension::<ScaledUiAmountConfig>() {
+        let multiplier = if unix_timestamp >= i64::from(config.new_multiplier_effective_timestamp) {
+            f64::from(config.new_multiplier)
+        } else {
+            f64::from(config.multiplier)
+        };
+
+        return Ok(multiplier);
+    }
+
+    Ok(1.0)
+}
+
+/// Shares minted for depositing `amount` into a vault holding `total_assets`
+/// backing `total_shares`, with both valued at `ui_scale`. Rounds down, in
+/// favour of existing holders.
+pub fn shares_for_deposit(amount: u64, total_assets: u64, total_shares: u64, ui_scale: f64) -> Result<u64> {
+    let deposit_value = ui_value(amount, ui_scale, false)?;
+    if deposit_value == 0 || total_shares == 0 || total_assets == 0 {
+        return Ok(deposit_value);
+    }
+
+    mul_div(deposit_value, total_shares, ui_value(total_assets, ui_scale, true)?)
+}
+
+/// Underlying paid out for burning `shares`. Rounds down, in favour of the
+/// holders that remain. Taken on raw amounts, the mint applies the same UI
+/// scale to the payout as to what the vault holds.
+pub fn amount_for_shares(shares: u64, total_assets: u64, total_shares: u64) -> Result<u64> {
+    require!(shares <= total_shares, VaultError::InsufficientBalance);
+
+    mul_div(shares, total_assets, total_shares)
+}
+
+/// UI amount of a raw `amount`, rounded up or down
+fn ui_value(amount: u64, ui_scale: f64, round_up: bool) -> Result<u64> {
+    let value = amount as f64 * ui_scale;
+    let value = if round_up { value.ceil() } else { value.floor() };
+    require!(value.is_finite() && value < u64::MAX as f64, VaultError::Overflow);
+
+    Ok(value as u64)
+}
+
+fn mul_div(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    let result = a as u128 * b as u128 / denominator as u128;
+    u64::try_from(result).map_err(|_| VaultError::Overflow.into())
+}
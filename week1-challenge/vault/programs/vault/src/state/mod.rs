@@ -1,3 +1,5 @@
 pub mod vault;
+pub mod user_position;
 
-pub use vault::*;
\ No newline at end of file
+pub use vault::*;
+pub use user_position::*;
\ No newline at end of file
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::{VaultError, MAX_BPS};
+
+/// Vault shares held by `user`
+#[account]
+pub struct UserPosition {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    /// Withdrawing before this costs the early withdraw penalty
+    pub unlock_time: i64,
+    /// Vault's early withdraw penalty when the user last deposited, in basis
+    /// points. Without one the position can't leave before it unlocks
+    pub early_withdraw_penalty_bps: u16,
+    /// Vault's treasury when the user last deposited, which the penalty goes to
+    pub treasury: Pubkey,
+    pub bump: u8,
+}
+
+impl UserPosition {
+    /// Part of `amount` sent to the treasury when it's withdrawn early.
+    /// Rounds up, in favour of the treasury.
+    pub fn early_withdraw_penalty(&self, amount: u64) -> Result<u64> {
+        let penalty = (amount as u128 * self.early_withdraw_penalty_bps as u128)
+            .div_ceil(MAX_BPS as u128);
+        u64::try_from(penalty).map_err(|_| VaultError::Overflow.into())
+    }
+}
@@ -1,7 +1,60 @@
 use anchor_lang::prelude::*;
+use crate::VaultError;
+
+/// Length of a withdrawal limit window
+pub const WITHDRAW_WINDOW_SECONDS: i64 = 24 * 60 * 60;
+
+pub const MAX_BPS: u16 = 10_000;
 
 #[account]
 pub struct Vault{
     pub mint: Pubkey,
+    /// Can pause the vault, change the withdrawal limit and hand over the role
+    pub admin: Pubkey,
+    /// Offered the admin role by `transfer_admin`, takes it with `accept_admin`
+    pub pending_admin: Option<Pubkey>,
+    /// Shares held across every `UserPosition`
+    pub total_shares: u64,
+    /// Tokens deposited and not yet withdrawn, which back `total_shares`.
+    /// Tokens sent straight to the vault's token account aren't counted
+    pub total_assets: u64,
+    /// Most tokens that can leave through `withdraw` per window, 0 for no limit
+    pub withdraw_limit: u64,
+    /// Window the `withdrawn` tally belongs to, counted in days since the epoch
+    pub withdraw_window: i64,
+    /// Tokens withdrawn during `withdraw_window`
+    pub withdrawn: u64,
+    /// Token account receiving early withdraw penalties on later deposits
+    pub treasury: Pubkey,
+    /// Cut of a withdrawal taken before the position unlocks, in basis points.
+    /// Positions keep the value from their last deposit
+    pub early_withdraw_penalty_bps: u16,
+    /// Transfer hook program of the mint when the vault was set up
+    pub hook_program: Option<Pubkey>,
+    pub paused: bool,
     pub bump: u8,
-}
\ No newline at end of file
+}
+
+impl Vault {
+    /// Counts `amount` against the current window's limit, starting a new
+    /// window once the day rolls over
+    pub fn record_withdrawal(&mut self, amount: u64, now: i64) -> Result<()> {
+        if self.withdraw_limit == 0 {
+            return Ok(());
+        }
+
+        let window = now.div_euclid(WITHDRAW_WINDOW_SECONDS);
+        if window != self.withdraw_window {
+            self.withdraw_window = window;
+            self.withdrawn = 0;
+        }
+
+        let withdrawn = self.withdrawn
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+        require!(withdrawn <= self.withdraw_limit, VaultError::WithdrawLimitExceeded);
+        self.withdrawn = withdrawn;
+
+        Ok(())
+    }
+}
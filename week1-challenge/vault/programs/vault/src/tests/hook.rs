@@ -23,15 +23,54 @@ mod tests {
         solana_keypair::Keypair,
         solana_native_token::LAMPORTS_PER_SOL,
         solana_pubkey::Pubkey,
-        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+        solana_sdk_ids::{bpf_loader_upgradeable::ID as BPF_LOADER_UPGRADEABLE_ID, system_program::ID as SYSTEM_PROGRAM_ID},
         solana_signer::Signer,
         solana_transaction::Transaction,
         std::path::PathBuf,
     };
-    use crate::Vault;
+    use crate::{UserPosition, Vault, VaultError};
 
     static PROGRAM_ID: Pubkey = crate::ID;
     static HOOK_PROGRAM_ID: Pubkey = pubkey!("YTRoGAwEK7wZ4Fmi6Pp5QFuKttcqViwBRNnKkgjptzZ");
+    static WHITELIST_HOOK_PROGRAM_ID: Pubkey = whitelist_transfer_hook::ID;
+
+    fn derive_program_data() -> Pubkey {
+        Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+    }
+
+    /// Redeploys the vault under the upgradeable loader, with `authority` as
+    /// its upgrade authority
+    fn set_upgrade_authority(svm: &mut LiteSVM, authority: &Pubkey) {
+        let vault_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy/vault.so");
+        let elf = std::fs::read(vault_so_path).expect("Failed to read vault program");
+        let program_data = derive_program_data();
+
+        // UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }, then the ELF
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&elf);
+        svm.set_account(program_data, Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+
+        // UpgradeableLoaderState::Program { programdata_address }
+        let mut data = vec![2, 0, 0, 0];
+        data.extend_from_slice(program_data.as_ref());
+        svm.set_account(PROGRAM_ID, Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: true,
+            rent_epoch: 0,
+        }).unwrap();
+    }
 
     /// Sets up LiteSVM and loads both programs
     fn setup() -> (LiteSVM, Keypair) {
@@ -47,6 +86,7 @@ mod tests {
         let vault_program_data = std::fs::read(vault_so_path)
             .expect("Failed to read vault program");
         svm.add_program(PROGRAM_ID, &vault_program_data);
+        set_upgrade_authority(&mut svm, &payer.pubkey());
 
         // Load hook program
         let hook_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -55,6 +95,13 @@ mod tests {
             .expect("Failed to read hook program");
         svm.add_program(HOOK_PROGRAM_ID, &hook_program_data);
 
+        // Load the mint-scoped whitelist hook
+        let whitelist_hook_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../../whitelist-transfer-hook/target/deploy/whitelist_transfer_hook.so");
+        let whitelist_hook_program_data = std::fs::read(whitelist_hook_so_path)
+            .expect("Failed to read whitelist hook program");
+        svm.add_program(WHITELIST_HOOK_PROGRAM_ID, &whitelist_hook_program_data);
+
         (svm, payer)
     }
 
@@ -64,6 +111,17 @@ mod tests {
         payer: &Keypair,
         hook_program: Pubkey,
         decimals: u8,
+    ) -> Keypair {
+        create_mint_with_hook_authority(svm, payer, hook_program, Some(payer.pubkey()), decimals)
+    }
+
+    /// Same, with `hook_authority` allowed to re-point the hook. `None` fixes it for good
+    fn create_mint_with_hook_authority(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        hook_program: Pubkey,
+        hook_authority: Option<Pubkey>,
+        decimals: u8,
     ) -> Keypair {
         let mint_keypair = Keypair::new();
 
@@ -83,7 +141,7 @@ mod tests {
         let init_hook_ix = transfer_hook::instruction::initialize(
             &spl_token_2022::ID,
             &mint_keypair.pubkey(),
-            Some(payer.pubkey()),
+            hook_authority,
             Some(hook_program),
         ).expect("Failed to create initialize transfer hook instruction");
 
@@ -109,7 +167,17 @@ mod tests {
 
     /// Helper to initialize vault
     fn initialize_vault(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey) -> Pubkey {
-        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault"], &PROGRAM_ID);
+        initialize_vault_with(svm, payer, mint, true).expect("Vault initialization failed");
+        find_vault_pda(mint).0
+    }
+
+    fn initialize_vault_with(
+        svm: &mut LiteSVM,
+        payer: &Keypair,
+        mint: &Pubkey,
+        allow_mutable_hook: bool,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
 
         let init_vault_ix = Instruction {
             program_id: PROGRAM_ID,
@@ -117,32 +185,36 @@ mod tests {
                 user: payer.pubkey(),
                 vault: vault_pda,
                 mint: *mint,
-                hook_program: HOOK_PROGRAM_ID,
                 token_program: spl_token_2022::ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                program: PROGRAM_ID,
+                program_data: derive_program_data(),
             }.to_account_metas(None),
-            data: crate::instruction::Initialize {}.data(),
+            data: crate::instruction::Initialize { allow_mutable_hook }.data(),
         };
 
-        let blockhash = svm.latest_blockhash();
-        let tx = Transaction::new_signed_with_payer(
-            &[init_vault_ix],
-            Some(&payer.pubkey()),
-            &[payer],
-            blockhash,
-        );
-
-        svm.send_transaction(tx).expect("Vault initialization failed");
-        vault_pda
+        send(svm, payer, init_vault_ix)
     }
 
-    /// Helper to initialize extra account meta list
+    /// Helper to initialize extra account meta list, along with the mint's
+    /// hook config with the payer as whitelist admin
     fn initialize_extra_account_meta_list(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey) -> Pubkey {
         let (extra_account_meta_list, _) = Pubkey::find_program_address(
             &[b"extra-account-metas", mint.as_ref()],
             &HOOK_PROGRAM_ID,
         );
 
+        let init_config_ix = Instruction {
+            program_id: HOOK_PROGRAM_ID,
+            accounts: hook::accounts::InitializeConfig {
+                authority: payer.pubkey(),
+                mint: *mint,
+                config: hook_config_pda(mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: hook::instruction::InitializeConfig { admin: payer.pubkey() }.data(),
+        };
+
         let init_extra_ix = Instruction {
             program_id: HOOK_PROGRAM_ID,
             accounts: vec![
@@ -156,7 +228,7 @@ mod tests {
 
         let blockhash = svm.latest_blockhash();
         let tx = Transaction::new_signed_with_payer(
-            &[init_extra_ix],
+            &[init_config_ix, init_extra_ix],
             Some(&payer.pubkey()),
             &[payer],
             blockhash,
@@ -165,22 +237,33 @@ mod tests {
         extra_account_meta_list
     }
 
-    /// Helper to whitelist a user
-    fn whitelist_user(svm: &mut LiteSVM, payer: &Keypair, user: &Pubkey) -> Pubkey {
-        let (whitelist_pda, _) = Pubkey::find_program_address(
-            &[b"hook", user.as_ref()],
-            &HOOK_PROGRAM_ID,
-        );
+    fn hook_config_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"config", mint.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    fn hook_whitelist_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"hook", mint.as_ref(), user.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    fn hook_counter_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"counter", mint.as_ref(), user.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    /// Helper to whitelist a user to both send and receive `mint`
+    fn whitelist_user(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        let whitelist_pda = hook_whitelist_pda(mint, user);
 
         let whitelist_ix = Instruction {
             program_id: HOOK_PROGRAM_ID,
             accounts: hook::accounts::AddToWhitelist {
                 whitelist: whitelist_pda,
-                authority: payer.pubkey(),
+                counter: hook_counter_pda(mint, user),
+                admin: payer.pubkey(),
+                config: hook_config_pda(mint),
                 user: *user,
                 system_program: SYSTEM_PROGRAM_ID,
             }.to_account_metas(None),
-            data: hook::instruction::AddToWhitelist {}.data(),
+            data: hook::instruction::AddToWhitelist { role: hook::Role::Both }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -234,13 +317,299 @@ mod tests {
         user_token_account
     }
 
+    /// Extra accounts the week1 hook resolves for transfers between token
+    /// accounts of `owners`
+    fn hook_accounts(mint: &Pubkey, owners: &[&Pubkey]) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(
+                Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &HOOK_PROGRAM_ID).0,
+                false,
+            ),
+        ];
+        accounts.extend(owners.iter().map(|owner| AccountMeta::new_readonly(hook_whitelist_pda(mint, owner), false)));
+        // The sender's volume counter is written by the hook
+        accounts.extend(owners.iter().map(|owner| AccountMeta::new(hook_counter_pda(mint, owner), false)));
+        accounts
+    }
+
+    fn find_vault_pda(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"vault", mint.as_ref()], &PROGRAM_ID)
+    }
+
+    fn position_pda(vault: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"position", vault.as_ref(), user.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn read_position(svm: &LiteSVM, vault: &Pubkey, user: &Pubkey) -> Option<UserPosition> {
+        let account = svm.get_account(&position_pda(vault, user))?;
+        if account.lamports == 0 {
+            return None;
+        }
+        Some(UserPosition::try_deserialize(&mut account.data.as_ref()).expect("Failed to deserialize position"))
+    }
+
+    fn mint_hook_program(svm: &LiteSVM, mint: &Pubkey) -> Option<Pubkey> {
+        let account = svm.get_account(mint).expect("Mint not found");
+        let mint_state = StateWithExtensions::<MintState>::unpack(&account.data).expect("Failed to unpack mint");
+        transfer_hook::get_program_id(&mint_state)
+    }
+
+    fn token_owner(svm: &LiteSVM, token_account: &Pubkey) -> Pubkey {
+        let account = svm.get_account(token_account).expect("Token account not found");
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("Failed to unpack token account")
+            .base
+            .owner
+    }
+
+    fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = svm.get_account(token_account).expect("Token account not found");
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("Failed to unpack token account")
+            .base
+            .amount
+    }
+
+    /// Helper to create a user holding `amount` tokens, whitelisted if the mint uses the week1 hook
+    fn fund_user(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, amount: u64) -> (Keypair, Pubkey) {
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL).expect("Failed to airdrop SOL");
+        if mint_hook_program(svm, mint) == Some(HOOK_PROGRAM_ID) {
+            whitelist_user(svm, payer, mint, &user.pubkey());
+        }
+
+        let user_token_account = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &user.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        );
+
+        let create_ata_ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &user.pubkey(),
+            mint,
+            &spl_token_2022::ID,
+        );
+
+        let mint_to_ix = instruction::mint_to(
+            &spl_token_2022::ID,
+            mint,
+            &user_token_account,
+            &payer.pubkey(),
+            &[],
+            amount,
+        ).expect("Failed to create mint_to instruction");
+
+        let blockhash = svm.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            &[create_ata_ix, mint_to_ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            blockhash,
+        );
+        svm.send_transaction(tx).expect("Mint to user failed");
+        (user, user_token_account)
+    }
+
+    fn deposit(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let extra_accounts = hook_accounts(mint, &[&user.pubkey(), &vault_pda]);
+        deposit_with_accounts(svm, user, user_token_account, mint, amount, extra_accounts)
+    }
+
+    /// Deposit passing `extra_accounts` as the remaining accounts
+    fn deposit_with_accounts(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> std::result::Result<(), String> {
+        let deposit_ix = deposit_ix(user, user_token_account, mint, amount, 0, extra_accounts);
+        send(svm, user, deposit_ix)
+    }
+
+    /// Deposit locking the position for `lock_duration` seconds
+    fn deposit_locked(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        lock_duration: i64,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let extra_accounts = hook_accounts(mint, &[&user.pubkey(), &vault_pda]);
+        let deposit_ix = deposit_ix(user, user_token_account, mint, amount, lock_duration, extra_accounts);
+        send(svm, user, deposit_ix)
+    }
+
+    fn deposit_ix(
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        lock_duration: i64,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        let (vault_pda, _) = find_vault_pda(mint);
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Deposit {
+                user: user.pubkey(),
+                user_token_account: *user_token_account,
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, &user.pubkey()),
+                vault_token_account: anchor_spl::associated_token::get_associated_token_address_with_program_id(
+                    &vault_pda,
+                    mint,
+                    &spl_token_2022::ID,
+                ),
+                mint: *mint,
+                token_program: spl_token_2022::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(extra_accounts)
+                .collect(),
+            data: crate::instruction::Deposit { amount, lock_duration }.data(),
+        }
+    }
+
+    fn withdraw(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        shares: u64,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let extra_accounts = hook_accounts(mint, &[&vault_pda, &user.pubkey()]);
+        withdraw_with_accounts(svm, user, user_token_account, mint, shares, extra_accounts)
+    }
+
+    /// Withdraw passing `extra_accounts` as the remaining accounts
+    fn withdraw_with_accounts(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        shares: u64,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> std::result::Result<(), String> {
+        let withdraw_ix = withdraw_ix(user, user_token_account, mint, shares, false, None, extra_accounts);
+        send(svm, user, withdraw_ix)
+    }
+
+    /// Withdraw from a locked position, paying the penalty into `treasury`
+    fn withdraw_early(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        shares: u64,
+        treasury: Option<Pubkey>,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let mut owners = vec![vault_pda, user.pubkey()];
+        if let Some(treasury) = treasury {
+            owners.push(token_owner(svm, &treasury));
+        }
+        let extra_accounts = hook_accounts(mint, &owners.iter().collect::<Vec<_>>());
+        let withdraw_ix = withdraw_ix(user, user_token_account, mint, shares, true, treasury, extra_accounts);
+        send(svm, user, withdraw_ix)
+    }
+
+    fn withdraw_ix(
+        user: &Keypair,
+        user_token_account: &Pubkey,
+        mint: &Pubkey,
+        shares: u64,
+        accept_penalty: bool,
+        treasury: Option<Pubkey>,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        let (vault_pda, _) = find_vault_pda(mint);
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Withdraw {
+                user: user.pubkey(),
+                user_token_account: *user_token_account,
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, &user.pubkey()),
+                vault_token_account: anchor_spl::associated_token::get_associated_token_address_with_program_id(
+                    &vault_pda,
+                    mint,
+                    &spl_token_2022::ID,
+                ),
+                treasury_token_account: treasury,
+                mint: *mint,
+                token_program: spl_token_2022::ID,
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(extra_accounts)
+                .collect(),
+            data: crate::instruction::Withdraw { shares, accept_penalty }.data(),
+        }
+    }
+
+    fn close_position(svm: &mut LiteSVM, user: &Keypair, mint: &Pubkey) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let close_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ClosePosition {
+                user: user.pubkey(),
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, &user.pubkey()),
+            }.to_account_metas(None),
+            data: crate::instruction::ClosePosition {}.data(),
+        };
+
+        send(svm, user, close_ix)
+    }
+
+    fn send(svm: &mut LiteSVM, signer: &Keypair, ix: Instruction) -> std::result::Result<(), String> {
+        svm.expire_blockhash();
+        let blockhash = svm.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+        svm.send_transaction(tx)
+            .map(|_| ())
+            .map_err(|e| format!("{:?}", e.err))
+    }
+
+    fn assert_vault_error(result: std::result::Result<(), String>, error: VaultError) {
+        let err = result.expect_err("Expected the transaction to fail");
+        let code = format!("Custom({})", u32::from(error));
+        assert!(err.contains(&code), "Expected {}, got {}", code, err);
+    }
+
+    /// Sets up a hooked mint and a vault whitelisted to send it
+    fn setup_vault() -> (LiteSVM, Keypair, Pubkey, Pubkey) {
+        let (mut svm, payer) = setup();
+
+        let mint = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6).pubkey();
+        let vault_pda = initialize_vault(&mut svm, &payer, &mint);
+        initialize_extra_account_meta_list(&mut svm, &payer, &mint);
+        whitelist_user(&mut svm, &payer, &mint, &vault_pda);
+
+        (svm, payer, mint, vault_pda)
+    }
+
     #[test]
     fn test_init_with_transfer_hook() {
         let (mut svm, payer) = setup();
-        let (vault_pda, vault_bump) = Pubkey::find_program_address(&[b"vault"], &PROGRAM_ID);
-
         let mint_keypair = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6);
         msg!("Mint: {}", mint_keypair.pubkey());
+        let (vault_pda, vault_bump) = find_vault_pda(&mint_keypair.pubkey());
 
         let init_vault_ix = Instruction {
             program_id: PROGRAM_ID,
@@ -248,11 +617,12 @@ mod tests {
                 user: payer.pubkey(),
                 vault: vault_pda,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
                 token_program: spl_token_2022::ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                program: PROGRAM_ID,
+                program_data: derive_program_data(),
             }.to_account_metas(None),
-            data: crate::instruction::Initialize {}.data(),
+            data: crate::instruction::Initialize { allow_mutable_hook: true }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -298,7 +668,7 @@ mod tests {
 
         let mint_keypair = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6);
         let vault_pda = initialize_vault(&mut svm, &payer, &mint_keypair.pubkey());
-        let extra_account_meta_list = initialize_extra_account_meta_list(&mut svm, &payer, &mint_keypair.pubkey());
+        initialize_extra_account_meta_list(&mut svm, &payer, &mint_keypair.pubkey());
 
         msg!("User NOT whitelisted. Expect deposit to fail");
 
@@ -319,16 +689,17 @@ mod tests {
                 user: payer.pubkey(),
                 user_token_account,
                 vault: vault_pda,
+                user_position: position_pda(&vault_pda, &payer.pubkey()),
                 vault_token_account,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
-                extra_account_meta_list,
-                user_whitelist: Default::default(),
                 token_program: spl_token_2022::ID,
                 associated_token_program: anchor_spl::associated_token::ID,
                 system_program: SYSTEM_PROGRAM_ID,
-            }.to_account_metas(None),
-            data: crate::instruction::Deposit { amount: deposit_amount }.data(),
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(&mint_keypair.pubkey(), &[&payer.pubkey(), &vault_pda]))
+                .collect(),
+            data: crate::instruction::Deposit { amount: deposit_amount, lock_duration: 0 }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -365,8 +736,9 @@ mod tests {
 
         let mint_keypair = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6);
         let vault_pda = initialize_vault(&mut svm, &payer, &mint_keypair.pubkey());
-        let extra_account_meta_list = initialize_extra_account_meta_list(&mut svm, &payer, &mint_keypair.pubkey());
-        let user_whitelist = whitelist_user(&mut svm, &payer, &payer.pubkey());
+        initialize_extra_account_meta_list(&mut svm, &payer, &mint_keypair.pubkey());
+        whitelist_user(&mut svm, &payer, &mint_keypair.pubkey(), &payer.pubkey());
+        whitelist_user(&mut svm, &payer, &mint_keypair.pubkey(), &vault_pda);
 
         let initial_amount = 100u64;
         let user_token_account = mint_tokens_to_user(&mut svm, &payer, &mint_keypair.pubkey(), initial_amount);
@@ -385,16 +757,17 @@ mod tests {
                 user: payer.pubkey(),
                 user_token_account,
                 vault: vault_pda,
+                user_position: position_pda(&vault_pda, &payer.pubkey()),
                 vault_token_account,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
-                extra_account_meta_list,
-                user_whitelist,
                 token_program: spl_token_2022::ID,
                 associated_token_program: anchor_spl::associated_token::ID,
                 system_program: SYSTEM_PROGRAM_ID,
-            }.to_account_metas(None),
-            data: crate::instruction::Deposit { amount: deposit_amount }.data(),
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(&mint_keypair.pubkey(), &[&payer.pubkey(), &vault_pda]))
+                .collect(),
+            data: crate::instruction::Deposit { amount: deposit_amount, lock_duration: 0 }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -429,11 +802,11 @@ mod tests {
 
         let mint_keypair = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6);
         let vault_pda = initialize_vault(&mut svm, &payer, &mint_keypair.pubkey());
-        let extra_account_meta_list = initialize_extra_account_meta_list(&mut svm, &payer, &mint_keypair.pubkey());
+        initialize_extra_account_meta_list(&mut svm, &payer, &mint_keypair.pubkey());
 
         // Whitelist both user and vault
-        let user_whitelist = whitelist_user(&mut svm, &payer, &payer.pubkey());
-        let vault_whitelist = whitelist_user(&mut svm, &payer, &vault_pda);
+        whitelist_user(&mut svm, &payer, &mint_keypair.pubkey(), &payer.pubkey());
+        whitelist_user(&mut svm, &payer, &mint_keypair.pubkey(), &vault_pda);
 
         let initial_amount = 100u64;
         let user_token_account = mint_tokens_to_user(&mut svm, &payer, &mint_keypair.pubkey(), initial_amount);
@@ -452,16 +825,17 @@ mod tests {
                 user: payer.pubkey(),
                 user_token_account,
                 vault: vault_pda,
+                user_position: position_pda(&vault_pda, &payer.pubkey()),
                 vault_token_account,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
-                extra_account_meta_list,
-                user_whitelist,
                 token_program: spl_token_2022::ID,
                 associated_token_program: anchor_spl::associated_token::ID,
                 system_program: SYSTEM_PROGRAM_ID,
-            }.to_account_metas(None),
-            data: crate::instruction::Deposit { amount: deposit_amount }.data(),
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(&mint_keypair.pubkey(), &[&payer.pubkey(), &vault_pda]))
+                .collect(),
+            data: crate::instruction::Deposit { amount: deposit_amount, lock_duration: 0 }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -481,14 +855,16 @@ mod tests {
                 user: payer.pubkey(),
                 user_token_account,
                 vault: vault_pda,
+                user_position: position_pda(&vault_pda, &payer.pubkey()),
                 vault_token_account,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
-                extra_account_meta_list,
-                vault_whitelist,
+                treasury_token_account: None,
                 token_program: spl_token_2022::ID,
-            }.to_account_metas(None),
-            data: crate::instruction::Withdraw { amount: withdraw_amount }.data(),
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(&mint_keypair.pubkey(), &[&vault_pda, &payer.pubkey()]))
+                .collect(),
+            data: crate::instruction::Withdraw { shares: withdraw_amount, accept_penalty: false }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -516,4 +892,818 @@ mod tests {
 
         msg!("Test passed!");
     }
+
+    #[test]
+    fn test_user_cannot_withdraw_another_users_deposit() {
+        let (mut svm, payer, mint, vault_pda) = setup_vault();
+        let (user_a, user_a_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        let (user_b, user_b_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        deposit(&mut svm, &user_a, &user_a_tokens, &mint, 80).expect("User A deposit failed");
+        deposit(&mut svm, &user_b, &user_b_tokens, &mint, 10).expect("User B deposit failed");
+
+        let position_a = read_position(&svm, &vault_pda, &user_a.pubkey()).unwrap();
+        assert_eq!((position_a.user, position_a.shares), (user_a.pubkey(), 80));
+        let position_b = read_position(&svm, &vault_pda, &user_b.pubkey()).unwrap();
+        assert_eq!((position_b.user, position_b.shares), (user_b.pubkey(), 10));
+
+        // The vault holds 90, but user B only put in 10
+        let result = withdraw(&mut svm, &user_b, &user_b_tokens, &mint, 50);
+        assert_vault_error(result, VaultError::InsufficientBalance);
+        let result = withdraw(&mut svm, &user_b, &user_b_tokens, &mint, 11);
+        assert_vault_error(result, VaultError::InsufficientBalance);
+
+        withdraw(&mut svm, &user_b, &user_b_tokens, &mint, 10).expect("User B withdraw failed");
+        assert_eq!(token_balance(&svm, &user_b_tokens), 100);
+        assert_eq!(read_position(&svm, &vault_pda, &user_b.pubkey()).unwrap().shares, 0);
+
+        // User A's deposit is untouched
+        assert_eq!(read_position(&svm, &vault_pda, &user_a.pubkey()).unwrap().shares, 80);
+        withdraw(&mut svm, &user_a, &user_a_tokens, &mint, 80).expect("User A withdraw failed");
+        assert_eq!(token_balance(&svm, &user_a_tokens), 100);
+    }
+
+    #[test]
+    fn test_withdraw_without_position_fails() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        let (user_a, user_a_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        let (user_b, user_b_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        deposit(&mut svm, &user_a, &user_a_tokens, &mint, 100).expect("User A deposit failed");
+
+        // User B never deposited, so there's no position to debit
+        let result = withdraw(&mut svm, &user_b, &user_b_tokens, &mint, 1);
+        assert!(result.is_err(), "Withdraw without a position should fail");
+        assert_eq!(token_balance(&svm, &user_b_tokens), 100);
+    }
+
+    #[test]
+    fn test_close_position() {
+        let (mut svm, payer, mint, vault_pda) = setup_vault();
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        deposit(&mut svm, &user, &user_tokens, &mint, 40).expect("Deposit failed");
+
+        let result = close_position(&mut svm, &user, &mint);
+        assert_vault_error(result, VaultError::PositionNotEmpty);
+
+        withdraw(&mut svm, &user, &user_tokens, &mint, 40).expect("Withdraw failed");
+        close_position(&mut svm, &user, &mint).expect("Close position failed");
+        assert!(read_position(&svm, &vault_pda, &user.pubkey()).is_none());
+
+        // Depositing again opens a fresh position
+        deposit(&mut svm, &user, &user_tokens, &mint, 25).expect("Deposit failed");
+        assert_eq!(read_position(&svm, &vault_pda, &user.pubkey()).unwrap().shares, 25);
+    }
+
+    #[test]
+    fn test_vault_per_mint() {
+        let (mut svm, payer) = setup();
+
+        let mint_x = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6).pubkey();
+        let mint_y = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 9).pubkey();
+
+        // Both mints get their own vault in the same program
+        let vault_x = initialize_vault(&mut svm, &payer, &mint_x);
+        let vault_y = initialize_vault(&mut svm, &payer, &mint_y);
+        assert_ne!(vault_x, vault_y);
+
+        for (mint, vault) in [(mint_x, vault_x), (mint_y, vault_y)] {
+            initialize_extra_account_meta_list(&mut svm, &payer, &mint);
+            whitelist_user(&mut svm, &payer, &mint, &vault);
+
+            let vault_account = svm.get_account(&vault).expect("Vault not found");
+            let vault_data = Vault::try_deserialize(&mut vault_account.data.as_ref()).unwrap();
+            assert_eq!(vault_data.mint, mint);
+        }
+
+        let (user_x, user_x_tokens) = fund_user(&mut svm, &payer, &mint_x, 100);
+        let (user_y, user_y_tokens) = fund_user(&mut svm, &payer, &mint_y, 100);
+
+        deposit(&mut svm, &user_x, &user_x_tokens, &mint_x, 40).expect("Deposit into vault X failed");
+        deposit(&mut svm, &user_y, &user_y_tokens, &mint_y, 70).expect("Deposit into vault Y failed");
+
+        let vault_x_tokens = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &vault_x,
+            &mint_x,
+            &spl_token_2022::ID,
+        );
+        let vault_y_tokens = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &vault_y,
+            &mint_y,
+            &spl_token_2022::ID,
+        );
+        assert_eq!(token_balance(&svm, &vault_x_tokens), 40);
+        assert_eq!(token_balance(&svm, &vault_y_tokens), 70);
+
+        // Emptying vault X leaves vault Y alone
+        withdraw(&mut svm, &user_x, &user_x_tokens, &mint_x, 40).expect("Withdraw from vault X failed");
+        assert_eq!(token_balance(&svm, &vault_x_tokens), 0);
+        assert_eq!(token_balance(&svm, &vault_y_tokens), 70);
+        assert_eq!(read_position(&svm, &vault_y, &user_y.pubkey()).unwrap().shares, 70);
+
+        // A mint still can't have two vaults
+        svm.expire_blockhash();
+        let (vault_pda, _) = find_vault_pda(&mint_x);
+        let init_vault_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Initialize {
+                user: payer.pubkey(),
+                mint: mint_x,
+                vault: vault_pda,
+                token_program: spl_token_2022::ID,
+                system_program: SYSTEM_PROGRAM_ID,
+                program: PROGRAM_ID,
+                program_data: derive_program_data(),
+            }.to_account_metas(None),
+            data: crate::instruction::Initialize { allow_mutable_hook: true }.data(),
+        };
+        assert!(send(&mut svm, &payer, init_vault_ix).is_err(), "Second vault for the same mint should fail");
+    }
+
+    fn whitelist_hook_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), user.as_ref()], &WHITELIST_HOOK_PROGRAM_ID).0
+    }
+
+    /// Extra accounts the mint-scoped whitelist hook resolves for a transfer
+    /// from `sender` to a token account owned by `recipient`
+    fn whitelist_hook_accounts(mint: &Pubkey, sender: &Pubkey, recipient: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(WHITELIST_HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(
+                Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &WHITELIST_HOOK_PROGRAM_ID).0,
+                false,
+            ),
+            AccountMeta::new_readonly(whitelist_hook_pda(mint, sender), false),
+            AccountMeta::new_readonly(*recipient, false),
+            AccountMeta::new_readonly(whitelist_hook_pda(mint, recipient), false),
+        ]
+    }
+
+    /// Sets up a mint using the mint-scoped whitelist hook, with the vault whitelisted
+    fn setup_whitelist_hook_vault(svm: &mut LiteSVM, payer: &Keypair) -> (Pubkey, Pubkey) {
+        let mint = create_mint_with_hook(svm, payer, WHITELIST_HOOK_PROGRAM_ID, 6).pubkey();
+        let vault_pda = initialize_vault(svm, payer, &mint);
+
+        let init_extra_ix = Instruction {
+            program_id: WHITELIST_HOOK_PROGRAM_ID,
+            accounts: whitelist_transfer_hook::accounts::InitializeExtraAccountMetaList {
+                payer: payer.pubkey(),
+                extra_account_meta_list: Pubkey::find_program_address(
+                    &[b"extra-account-metas", mint.as_ref()],
+                    &WHITELIST_HOOK_PROGRAM_ID,
+                ).0,
+                mint,
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: whitelist_transfer_hook::instruction::InitializeTransferHook {}.data(),
+        };
+        send(svm, payer, init_extra_ix).expect("ExtraAccountMetaList init failed");
+        whitelist_for_mint(svm, payer, &mint, &vault_pda);
+
+        (mint, vault_pda)
+    }
+
+    fn whitelist_for_mint(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, user: &Pubkey) {
+        let whitelist_ix = Instruction {
+            program_id: WHITELIST_HOOK_PROGRAM_ID,
+            accounts: whitelist_transfer_hook::accounts::InitializeWhitelist {
+                admin: payer.pubkey(),
+                user: *user,
+                mint: *mint,
+                whitelist: whitelist_hook_pda(mint, user),
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: whitelist_transfer_hook::instruction::InitializeWhitelist {}.data(),
+        };
+        send(svm, payer, whitelist_ix).expect("Whitelist failed");
+    }
+
+    /// Helper to create a Token-2022 mint with no extensions
+    fn create_plain_mint(svm: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+        let mint_keypair = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&[])
+            .expect("Failed to calculate mint space");
+        let rent = svm.minimum_balance_for_rent_exemption(space);
+
+        let create_account_ix = solana_system_interface::instruction::create_account(
+            &payer.pubkey(),
+            &mint_keypair.pubkey(),
+            rent,
+            space as u64,
+            &spl_token_2022::ID,
+        );
+
+        let init_mint_ix = instruction::initialize_mint(
+            &spl_token_2022::ID,
+            &mint_keypair.pubkey(),
+            &payer.pubkey(),
+            None,
+            6,
+        ).expect("Failed to create initialize mint instruction");
+
+        let blockhash = svm.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(
+            &[create_account_ix, init_mint_ix],
+            Some(&payer.pubkey()),
+            &[payer, &mint_keypair],
+            blockhash,
+        );
+
+        svm.send_transaction(tx).expect("Mint creation failed");
+        mint_keypair.pubkey()
+    }
+
+    #[test]
+    fn test_vault_with_whitelist_hook() {
+        let (mut svm, payer) = setup();
+        let (mint, vault_pda) = setup_whitelist_hook_vault(&mut svm, &payer);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        whitelist_for_mint(&mut svm, &payer, &mint, &user.pubkey());
+
+        let accounts = whitelist_hook_accounts(&mint, &user.pubkey(), &vault_pda);
+        deposit_with_accounts(&mut svm, &user, &user_tokens, &mint, 60, accounts).expect("Deposit failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 40);
+
+        let accounts = whitelist_hook_accounts(&mint, &vault_pda, &user.pubkey());
+        withdraw_with_accounts(&mut svm, &user, &user_tokens, &mint, 60, accounts).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    #[test]
+    fn test_vault_with_plain_mint() {
+        let (mut svm, payer) = setup();
+        let mint = create_plain_mint(&mut svm, &payer);
+        initialize_vault(&mut svm, &payer, &mint);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        // No hook, so no remaining accounts
+        deposit_with_accounts(&mut svm, &user, &user_tokens, &mint, 30, vec![]).expect("Deposit failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 70);
+
+        withdraw_with_accounts(&mut svm, &user, &user_tokens, &mint, 30, vec![]).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    #[test]
+    fn test_donation_does_not_move_share_price() {
+        let (mut svm, payer) = setup();
+        let mint = create_plain_mint(&mut svm, &payer);
+        let vault_pda = initialize_vault(&mut svm, &payer, &mint);
+        let (attacker, attacker_tokens) = fund_user(&mut svm, &payer, &mint, 1_000_001);
+        let (victim, victim_tokens) = fund_user(&mut svm, &payer, &mint, 1_000);
+
+        // The attacker takes the first share, then sends the rest straight to
+        // the vault's token account
+        deposit_with_accounts(&mut svm, &attacker, &attacker_tokens, &mint, 1, vec![]).expect("Deposit failed");
+        let vault_tokens = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &vault_pda,
+            &mint,
+            &spl_token_2022::ID,
+        );
+        let donate_ix = instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &attacker_tokens,
+            &mint,
+            &vault_tokens,
+            &attacker.pubkey(),
+            &[],
+            1_000_000,
+            6,
+        ).expect("Failed to create transfer_checked instruction");
+        send(&mut svm, &attacker, donate_ix).expect("Donation failed");
+
+        // The donation isn't counted, so the victim still gets a share per token
+        deposit_with_accounts(&mut svm, &victim, &victim_tokens, &mint, 1_000, vec![]).expect("Deposit failed");
+        assert_eq!(read_position(&svm, &vault_pda, &victim.pubkey()).unwrap().shares, 1_000);
+        assert_eq!(read_vault(&svm, &mint).total_assets, 1_001);
+
+        withdraw_with_accounts(&mut svm, &victim, &victim_tokens, &mint, 1_000, vec![]).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &victim_tokens), 1_000);
+        withdraw_with_accounts(&mut svm, &attacker, &attacker_tokens, &mint, 1, vec![]).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &attacker_tokens), 1);
+    }
+
+    #[test]
+    fn test_deposit_rejects_wrong_hook_accounts() {
+        let (mut svm, payer) = setup();
+        let (mint, vault_pda) = setup_whitelist_hook_vault(&mut svm, &payer);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        whitelist_for_mint(&mut svm, &payer, &mint, &user.pubkey());
+
+        // The week1 hook's accounts, but the mint points at the whitelist hook
+        let accounts = hook_accounts(&mint, &[&user.pubkey(), &vault_pda]);
+        let result = deposit_with_accounts(&mut svm, &user, &user_tokens, &mint, 10, accounts);
+        assert_vault_error(result, VaultError::HookProgramMismatch);
+
+        // No accounts at all for a hooked mint
+        let result = deposit_with_accounts(&mut svm, &user, &user_tokens, &mint, 10, vec![]);
+        assert_vault_error(result, VaultError::HookProgramMismatch);
+
+        // Right program, but without its ExtraAccountMetaList
+        let mut accounts = whitelist_hook_accounts(&mint, &user.pubkey(), &vault_pda);
+        accounts.remove(1);
+        let result = deposit_with_accounts(&mut svm, &user, &user_tokens, &mint, 10, accounts);
+        assert_vault_error(result, VaultError::MissingExtraAccountMetas);
+
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    fn read_vault(svm: &LiteSVM, mint: &Pubkey) -> Vault {
+        let account = svm.get_account(&find_vault_pda(mint).0).expect("Vault not found");
+        Vault::try_deserialize(&mut account.data.as_ref()).expect("Failed to deserialize vault")
+    }
+
+    fn set_clock(svm: &mut LiteSVM, unix_timestamp: i64) {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        svm.set_sysvar::<Clock>(&clock);
+    }
+
+    /// Sends an admin instruction for the vault of `mint` signed by `admin`
+    fn vault_admin(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        mint: &Pubkey,
+        data: Vec<u8>,
+    ) -> std::result::Result<(), String> {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::VaultAdmin {
+                admin: admin.pubkey(),
+                vault: find_vault_pda(mint).0,
+            }.to_account_metas(None),
+            data,
+        };
+
+        send(svm, admin, ix)
+    }
+
+    fn emergency_withdraw(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        user: &Pubkey,
+        owner_token_account: &Pubkey,
+        mint: &Pubkey,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::EmergencyWithdraw {
+                admin: admin.pubkey(),
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, user),
+                owner_token_account: *owner_token_account,
+                vault_token_account: anchor_spl::associated_token::get_associated_token_address_with_program_id(
+                    &vault_pda,
+                    mint,
+                    &spl_token_2022::ID,
+                ),
+                mint: *mint,
+                token_program: spl_token_2022::ID,
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(mint, &[&vault_pda, user]))
+                .collect(),
+            data: crate::instruction::EmergencyWithdraw {}.data(),
+        };
+
+        send(svm, admin, ix)
+    }
+
+    #[test]
+    fn test_pause_blocks_deposit_and_withdraw() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        deposit(&mut svm, &user, &user_tokens, &mint, 50).expect("Deposit failed");
+
+        assert_eq!(read_vault(&svm, &mint).admin, payer.pubkey());
+
+        // Only the admin can pause
+        let result = vault_admin(&mut svm, &user, &mint, crate::instruction::Pause {}.data());
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        vault_admin(&mut svm, &payer, &mint, crate::instruction::Pause {}.data()).expect("Pause failed");
+        assert!(read_vault(&svm, &mint).paused);
+
+        let result = deposit(&mut svm, &user, &user_tokens, &mint, 10);
+        assert_vault_error(result, VaultError::VaultPaused);
+        let result = withdraw(&mut svm, &user, &user_tokens, &mint, 10);
+        assert_vault_error(result, VaultError::VaultPaused);
+
+        // Only the admin can unpause
+        let result = vault_admin(&mut svm, &user, &mint, crate::instruction::Unpause {}.data());
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        vault_admin(&mut svm, &payer, &mint, crate::instruction::Unpause {}.data()).expect("Unpause failed");
+        let result = vault_admin(&mut svm, &payer, &mint, crate::instruction::Unpause {}.data());
+        assert_vault_error(result, VaultError::VaultNotPaused);
+
+        withdraw(&mut svm, &user, &user_tokens, &mint, 50).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    fn accept_admin(svm: &mut LiteSVM, new_admin: &Keypair, mint: &Pubkey) -> std::result::Result<(), String> {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AcceptAdmin {
+                new_admin: new_admin.pubkey(),
+                vault: find_vault_pda(mint).0,
+            }.to_account_metas(None),
+            data: crate::instruction::AcceptAdmin {}.data(),
+        };
+
+        send(svm, new_admin, ix)
+    }
+
+    #[test]
+    fn test_transfer_admin() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        let new_admin = Keypair::new();
+        svm.airdrop(&new_admin.pubkey(), LAMPORTS_PER_SOL).expect("Failed to airdrop SOL");
+
+        // Nobody else can offer the role
+        let data = crate::instruction::TransferAdmin { new_admin: new_admin.pubkey() }.data();
+        let result = vault_admin(&mut svm, &new_admin, &mint, data.clone());
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        // Nor take it without an offer
+        let result = accept_admin(&mut svm, &new_admin, &mint);
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        let result = vault_admin(
+            &mut svm,
+            &payer,
+            &mint,
+            crate::instruction::TransferAdmin { new_admin: Pubkey::default() }.data(),
+        );
+        assert_vault_error(result, VaultError::InvalidAdmin);
+
+        vault_admin(&mut svm, &payer, &mint, data).expect("Transfer admin failed");
+        let vault = read_vault(&svm, &mint);
+        assert_eq!((vault.admin, vault.pending_admin), (payer.pubkey(), Some(new_admin.pubkey())));
+
+        // Only the offered key can accept
+        let outsider = Keypair::new();
+        svm.airdrop(&outsider.pubkey(), LAMPORTS_PER_SOL).expect("Failed to airdrop SOL");
+        let result = accept_admin(&mut svm, &outsider, &mint);
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        accept_admin(&mut svm, &new_admin, &mint).expect("Accept admin failed");
+        let vault = read_vault(&svm, &mint);
+        assert_eq!((vault.admin, vault.pending_admin), (new_admin.pubkey(), None));
+
+        // The old admin has lost every permission
+        let result = vault_admin(&mut svm, &payer, &mint, crate::instruction::Pause {}.data());
+        assert_vault_error(result, VaultError::Unauthorized);
+        let result = vault_admin(&mut svm, &payer, &mint, crate::instruction::SetWithdrawLimit { limit: 1 }.data());
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        vault_admin(&mut svm, &new_admin, &mint, crate::instruction::Pause {}.data()).expect("Pause failed");
+        assert!(read_vault(&svm, &mint).paused);
+    }
+
+    #[test]
+    fn test_daily_withdraw_limit() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        let day = 24 * 60 * 60;
+        set_clock(&mut svm, 100 * day);
+
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 1_000);
+        deposit(&mut svm, &user, &user_tokens, &mint, 1_000).expect("Deposit failed");
+
+        let data = crate::instruction::SetWithdrawLimit { limit: 300 }.data();
+        let result = vault_admin(&mut svm, &user, &mint, data.clone());
+        assert_vault_error(result, VaultError::Unauthorized);
+        vault_admin(&mut svm, &payer, &mint, data).expect("Set withdraw limit failed");
+
+        withdraw(&mut svm, &user, &user_tokens, &mint, 200).expect("Withdraw failed");
+        let result = withdraw(&mut svm, &user, &user_tokens, &mint, 101);
+        assert_vault_error(result, VaultError::WithdrawLimitExceeded);
+        withdraw(&mut svm, &user, &user_tokens, &mint, 100).expect("Withdraw up to the limit failed");
+
+        // Still the same day
+        set_clock(&mut svm, 101 * day - 1);
+        let result = withdraw(&mut svm, &user, &user_tokens, &mint, 1);
+        assert_vault_error(result, VaultError::WithdrawLimitExceeded);
+
+        // The next day starts a fresh window
+        set_clock(&mut svm, 101 * day);
+        withdraw(&mut svm, &user, &user_tokens, &mint, 300).expect("Withdraw on the next day failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 600);
+
+        // Lifting the limit lets the rest out
+        vault_admin(&mut svm, &payer, &mint, crate::instruction::SetWithdrawLimit { limit: 0 }.data())
+            .expect("Set withdraw limit failed");
+        withdraw(&mut svm, &user, &user_tokens, &mint, 400).expect("Withdraw without a limit failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 1_000);
+    }
+
+    #[test]
+    fn test_emergency_withdraw() {
+        let (mut svm, payer, mint, vault_pda) = setup_vault();
+        let (user_a, user_a_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        let (user_b, user_b_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        deposit(&mut svm, &user_a, &user_a_tokens, &mint, 60).expect("User A deposit failed");
+        deposit(&mut svm, &user_b, &user_b_tokens, &mint, 40).expect("User B deposit failed");
+
+        // Only while paused
+        let result = emergency_withdraw(&mut svm, &payer, &user_a.pubkey(), &user_a_tokens, &mint);
+        assert_vault_error(result, VaultError::VaultNotPaused);
+
+        vault_admin(&mut svm, &payer, &mint, crate::instruction::Pause {}.data()).expect("Pause failed");
+
+        // Only by the admin
+        let result = emergency_withdraw(&mut svm, &user_b, &user_a.pubkey(), &user_a_tokens, &mint);
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        // Only to the position's owner
+        let result = emergency_withdraw(&mut svm, &payer, &user_a.pubkey(), &user_b_tokens, &mint);
+        assert!(result.is_err(), "Emergency withdraw to someone else should fail");
+
+        emergency_withdraw(&mut svm, &payer, &user_a.pubkey(), &user_a_tokens, &mint)
+            .expect("Emergency withdraw failed");
+        assert_eq!(token_balance(&svm, &user_a_tokens), 100);
+        assert_eq!(read_position(&svm, &vault_pda, &user_a.pubkey()).unwrap().shares, 0);
+
+        // User B's position is untouched
+        assert_eq!(read_position(&svm, &vault_pda, &user_b.pubkey()).unwrap().shares, 40);
+        assert_eq!(read_vault(&svm, &mint).total_shares, 40);
+    }
+
+    fn set_early_withdraw_penalty(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        mint: &Pubkey,
+        treasury: &Pubkey,
+        penalty_bps: u16,
+    ) -> std::result::Result<(), String> {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SetEarlyWithdrawPenalty {
+                admin: admin.pubkey(),
+                vault: find_vault_pda(mint).0,
+                treasury_token_account: *treasury,
+            }.to_account_metas(None),
+            data: crate::instruction::SetEarlyWithdrawPenalty { penalty_bps }.data(),
+        };
+
+        send(svm, admin, ix)
+    }
+
+    fn extend_lock(
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        mint: &Pubkey,
+        lock_duration: i64,
+    ) -> std::result::Result<(), String> {
+        let (vault_pda, _) = find_vault_pda(mint);
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ExtendLock {
+                user: user.pubkey(),
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, &user.pubkey()),
+            }.to_account_metas(None),
+            data: crate::instruction::ExtendLock { lock_duration }.data(),
+        };
+
+        send(svm, user, ix)
+    }
+
+    #[test]
+    fn test_locked_deposit() {
+        let (mut svm, payer, mint, vault_pda) = setup_vault();
+        set_clock(&mut svm, 1_000);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        let result = deposit_locked(&mut svm, &user, &user_tokens, &mint, 100, -1);
+        assert_vault_error(result, VaultError::InvalidLockDuration);
+
+        deposit_locked(&mut svm, &user, &user_tokens, &mint, 100, 500).expect("Deposit failed");
+        assert_eq!(read_position(&svm, &vault_pda, &user.pubkey()).unwrap().unlock_time, 1_500);
+
+        set_clock(&mut svm, 1_499);
+        let result = withdraw(&mut svm, &user, &user_tokens, &mint, 100);
+        assert_vault_error(result, VaultError::PositionLocked);
+
+        // No penalty was set, so there's no early way out either
+        let result = withdraw_early(&mut svm, &user, &user_tokens, &mint, 100, None);
+        assert_vault_error(result, VaultError::PositionLocked);
+
+        set_clock(&mut svm, 1_500);
+        withdraw(&mut svm, &user, &user_tokens, &mint, 100).expect("Withdraw after unlock failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    #[test]
+    fn test_deposit_keeps_later_unlock_time() {
+        let (mut svm, payer, mint, vault_pda) = setup_vault();
+        set_clock(&mut svm, 1_000);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        deposit_locked(&mut svm, &user, &user_tokens, &mint, 50, 500).expect("Deposit failed");
+
+        // A shorter lock doesn't bring the unlock time forward
+        deposit_locked(&mut svm, &user, &user_tokens, &mint, 50, 100).expect("Deposit failed");
+        assert_eq!(read_position(&svm, &vault_pda, &user.pubkey()).unwrap().unlock_time, 1_500);
+    }
+
+    #[test]
+    fn test_early_withdraw_penalty() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        set_clock(&mut svm, 1_000);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 1_000);
+        let (_, treasury) = fund_user(&mut svm, &payer, &mint, 0);
+        let (_, other_account) = fund_user(&mut svm, &payer, &mint, 0);
+
+        // 10%, only the admin can set it
+        let result = set_early_withdraw_penalty(&mut svm, &user, &mint, &treasury, 1_000);
+        assert_vault_error(result, VaultError::Unauthorized);
+        let result = set_early_withdraw_penalty(&mut svm, &payer, &mint, &treasury, 10_001);
+        assert_vault_error(result, VaultError::InvalidPenalty);
+        set_early_withdraw_penalty(&mut svm, &payer, &mint, &treasury, 1_000).expect("Set penalty failed");
+
+        deposit_locked(&mut svm, &user, &user_tokens, &mint, 1_000, 500).expect("Deposit failed");
+
+        // The penalty has to go to the configured treasury
+        let result = withdraw_early(&mut svm, &user, &user_tokens, &mint, 500, None);
+        assert_vault_error(result, VaultError::InvalidTreasury);
+        let result = withdraw_early(&mut svm, &user, &user_tokens, &mint, 500, Some(other_account));
+        assert_vault_error(result, VaultError::InvalidTreasury);
+
+        withdraw_early(&mut svm, &user, &user_tokens, &mint, 500, Some(treasury)).expect("Early withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 450);
+        assert_eq!(token_balance(&svm, &treasury), 50);
+
+        // New terms don't reach a position that's already locked
+        set_early_withdraw_penalty(&mut svm, &payer, &mint, &other_account, 10_000).expect("Set penalty failed");
+
+        // Rounds up for the treasury
+        withdraw_early(&mut svm, &user, &user_tokens, &mint, 5, Some(treasury)).expect("Early withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 454);
+        assert_eq!(token_balance(&svm, &treasury), 51);
+
+        // No penalty once unlocked
+        set_clock(&mut svm, 1_500);
+        withdraw_early(&mut svm, &user, &user_tokens, &mint, 495, Some(treasury)).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 949);
+        assert_eq!(token_balance(&svm, &treasury), 51);
+    }
+
+    #[test]
+    fn test_extend_lock() {
+        let (mut svm, payer, mint, vault_pda) = setup_vault();
+        set_clock(&mut svm, 1_000);
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        deposit_locked(&mut svm, &user, &user_tokens, &mint, 100, 500).expect("Deposit failed");
+
+        // Can't shorten the lock
+        let result = extend_lock(&mut svm, &user, &mint, 500);
+        assert_vault_error(result, VaultError::LockNotExtended);
+        let result = extend_lock(&mut svm, &user, &mint, 100);
+        assert_vault_error(result, VaultError::LockNotExtended);
+
+        set_clock(&mut svm, 1_200);
+        extend_lock(&mut svm, &user, &mint, 1_000).expect("Extend lock failed");
+        assert_eq!(read_position(&svm, &vault_pda, &user.pubkey()).unwrap().unlock_time, 2_200);
+
+        // Still locked past the original unlock time
+        set_clock(&mut svm, 1_500);
+        let result = withdraw(&mut svm, &user, &user_tokens, &mint, 100);
+        assert_vault_error(result, VaultError::PositionLocked);
+
+        set_clock(&mut svm, 2_200);
+        withdraw(&mut svm, &user, &user_tokens, &mint, 100).expect("Withdraw after unlock failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    fn update_hook_program(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, hook_program: Pubkey) {
+        let ix = transfer_hook::instruction::update(
+            &spl_token_2022::ID,
+            mint,
+            &payer.pubkey(),
+            &[],
+            Some(hook_program),
+        ).expect("Failed to create update transfer hook instruction");
+        send(svm, payer, ix).expect("Transfer hook update failed");
+    }
+
+    fn sync_hook_program(svm: &mut LiteSVM, admin: &Keypair, mint: &Pubkey) -> std::result::Result<(), String> {
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SyncHookProgram {
+                admin: admin.pubkey(),
+                vault: find_vault_pda(mint).0,
+                mint: *mint,
+            }.to_account_metas(None),
+            data: crate::instruction::SyncHookProgram {}.data(),
+        };
+
+        send(svm, admin, ix)
+    }
+
+    #[test]
+    fn test_initialize_records_hook_program() {
+        let (mut svm, payer) = setup();
+
+        // The hook authority could swap the hook out from under the vault
+        let mutable_mint = create_mint_with_hook(&mut svm, &payer, HOOK_PROGRAM_ID, 6).pubkey();
+        let result = initialize_vault_with(&mut svm, &payer, &mutable_mint, false);
+        assert_vault_error(result, VaultError::MutableTransferHook);
+
+        // Only the hook or upgrade authority can opt in
+        let outsider = Keypair::new();
+        svm.airdrop(&outsider.pubkey(), LAMPORTS_PER_SOL).expect("Failed to airdrop SOL");
+        let result = initialize_vault_with(&mut svm, &outsider, &mutable_mint, true);
+        assert_vault_error(result, VaultError::NotVaultCreator);
+        initialize_vault_with(&mut svm, &payer, &mutable_mint, true).expect("Opted-in initialization failed");
+        assert_eq!(read_vault(&svm, &mutable_mint).hook_program, Some(HOOK_PROGRAM_ID));
+
+        let fixed_mint = create_mint_with_hook_authority(&mut svm, &payer, HOOK_PROGRAM_ID, None, 6).pubkey();
+        initialize_vault_with(&mut svm, &payer, &fixed_mint, false).expect("Initialization failed");
+        assert_eq!(read_vault(&svm, &fixed_mint).hook_program, Some(HOOK_PROGRAM_ID));
+
+        let plain_mint = create_plain_mint(&mut svm, &payer);
+        initialize_vault_with(&mut svm, &payer, &plain_mint, false).expect("Initialization failed");
+        assert_eq!(read_vault(&svm, &plain_mint).hook_program, None);
+    }
+
+    #[test]
+    fn test_initialize_requires_hook_or_upgrade_authority() {
+        let (mut svm, payer) = setup();
+        let upgrade_authority = Keypair::new();
+        svm.airdrop(&upgrade_authority.pubkey(), LAMPORTS_PER_SOL).expect("Failed to airdrop SOL");
+        set_upgrade_authority(&mut svm, &upgrade_authority.pubkey());
+
+        // A fixed-supply mint, nobody can mint more of it
+        let hook_authority = Keypair::new();
+        svm.airdrop(&hook_authority.pubkey(), LAMPORTS_PER_SOL).expect("Failed to airdrop SOL");
+        let mint = create_mint_with_hook_authority(&mut svm, &payer, HOOK_PROGRAM_ID, Some(hook_authority.pubkey()), 6).pubkey();
+        let set_authority_ix = instruction::set_authority(
+            &spl_token_2022::ID,
+            &mint,
+            None,
+            instruction::AuthorityType::MintTokens,
+            &payer.pubkey(),
+            &[],
+        ).unwrap();
+        send(&mut svm, &payer, set_authority_ix).expect("Failed to drop the mint authority");
+        let mint_account = svm.get_account(&mint).unwrap();
+        assert!(StateWithExtensions::<MintState>::unpack(&mint_account.data).unwrap().base.mint_authority.is_none());
+
+        // Anyone else would become the vault's admin, the old mint authority included
+        let result = initialize_vault_with(&mut svm, &payer, &mint, true);
+        assert_vault_error(result, VaultError::NotVaultCreator);
+        assert!(svm.get_account(&find_vault_pda(&mint).0).is_none());
+
+        initialize_vault_with(&mut svm, &hook_authority, &mint, true).expect("Initialization failed");
+        assert_eq!(read_vault(&svm, &mint).admin, hook_authority.pubkey());
+
+        // Without a hook authority it's up to the upgrade authority
+        let fixed_mint = create_mint_with_hook_authority(&mut svm, &payer, HOOK_PROGRAM_ID, None, 6).pubkey();
+        let result = initialize_vault_with(&mut svm, &payer, &fixed_mint, false);
+        assert_vault_error(result, VaultError::NotVaultCreator);
+
+        initialize_vault_with(&mut svm, &upgrade_authority, &fixed_mint, false).expect("Initialization failed");
+        assert_eq!(read_vault(&svm, &fixed_mint).admin, upgrade_authority.pubkey());
+    }
+
+    #[test]
+    fn test_repointed_hook_blocks_transfers() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+        deposit(&mut svm, &user, &user_tokens, &mint, 50).expect("Deposit failed");
+
+        update_hook_program(&mut svm, &payer, &mint, WHITELIST_HOOK_PROGRAM_ID);
+
+        let result = deposit(&mut svm, &user, &user_tokens, &mint, 10);
+        assert_vault_error(result, VaultError::HookProgramChanged);
+        let result = withdraw(&mut svm, &user, &user_tokens, &mint, 10);
+        assert_vault_error(result, VaultError::HookProgramChanged);
+
+        // Pointing it back is enough
+        update_hook_program(&mut svm, &payer, &mint, HOOK_PROGRAM_ID);
+        withdraw(&mut svm, &user, &user_tokens, &mint, 50).expect("Withdraw failed");
+        assert_eq!(token_balance(&svm, &user_tokens), 100);
+    }
+
+    #[test]
+    fn test_sync_hook_program() {
+        let (mut svm, payer, mint, _) = setup_vault();
+        let (user, user_tokens) = fund_user(&mut svm, &payer, &mint, 100);
+
+        update_hook_program(&mut svm, &payer, &mint, WHITELIST_HOOK_PROGRAM_ID);
+
+        let result = sync_hook_program(&mut svm, &user, &mint);
+        assert_vault_error(result, VaultError::Unauthorized);
+
+        sync_hook_program(&mut svm, &payer, &mint).expect("Sync failed");
+        assert_eq!(read_vault(&svm, &mint).hook_program, Some(WHITELIST_HOOK_PROGRAM_ID));
+
+        // The vault now expects the new hook's accounts
+        let result = deposit(&mut svm, &user, &user_tokens, &mint, 10);
+        assert_vault_error(result, VaultError::HookProgramMismatch);
+    }
 }
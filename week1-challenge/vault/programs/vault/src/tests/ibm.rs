@@ -24,16 +24,100 @@ mod tests {
         solana_keypair::Keypair,
         solana_native_token::LAMPORTS_PER_SOL,
         solana_pubkey::Pubkey,
-        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+        solana_sdk_ids::{bpf_loader_upgradeable::ID as BPF_LOADER_UPGRADEABLE_ID, system_program::ID as SYSTEM_PROGRAM_ID},
         solana_signer::Signer,
         solana_transaction::Transaction,
         std::path::PathBuf,
     };
-    use crate::Vault;
+    use crate::{UserPosition, Vault};
 
     static PROGRAM_ID: Pubkey = crate::ID;
     static HOOK_PROGRAM_ID: Pubkey = pubkey!("YTRoGAwEK7wZ4Fmi6Pp5QFuKttcqViwBRNnKkgjptzZ");
 
+    /// Extra accounts the week1 hook resolves for transfers between token
+    /// accounts of `owners`
+    fn hook_accounts(mint: &Pubkey, owners: &[&Pubkey]) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(HOOK_PROGRAM_ID, false),
+            AccountMeta::new_readonly(
+                Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &HOOK_PROGRAM_ID).0,
+                false,
+            ),
+        ];
+        accounts.extend(owners.iter().map(|owner| AccountMeta::new_readonly(hook_whitelist_pda(mint, owner), false)));
+        // The sender's volume counter is written by the hook
+        accounts.extend(owners.iter().map(|owner| AccountMeta::new(hook_counter_pda(mint, owner), false)));
+        accounts
+    }
+
+    fn hook_config_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"config", mint.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    fn hook_whitelist_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"hook", mint.as_ref(), user.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    fn hook_counter_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"counter", mint.as_ref(), user.as_ref()], &HOOK_PROGRAM_ID).0
+    }
+
+    /// Sets up the week1 hook's config for `mint`, with `payer` as whitelist admin
+    fn initialize_hook_config_ix(payer: &Keypair, mint: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: HOOK_PROGRAM_ID,
+            accounts: hook::accounts::InitializeConfig {
+                authority: payer.pubkey(),
+                mint: *mint,
+                config: hook_config_pda(mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: hook::instruction::InitializeConfig { admin: payer.pubkey() }.data(),
+        }
+    }
+
+    fn position_pda(vault: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"position", vault.as_ref(), user.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn derive_program_data() -> Pubkey {
+        Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+    }
+
+    /// Redeploys the vault under the upgradeable loader, with `authority` as
+    /// its upgrade authority
+    fn set_upgrade_authority(svm: &mut LiteSVM, authority: &Pubkey) {
+        let vault_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy/vault.so");
+        let elf = std::fs::read(vault_so_path).expect("Failed to read vault program");
+        let program_data = derive_program_data();
+
+        // UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }, then the ELF
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&elf);
+        svm.set_account(program_data, Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: false,
+            rent_epoch: 0,
+        }).unwrap();
+
+        // UpgradeableLoaderState::Program { programdata_address }
+        let mut data = vec![2, 0, 0, 0];
+        data.extend_from_slice(program_data.as_ref());
+        svm.set_account(PROGRAM_ID, Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: true,
+            rent_epoch: 0,
+        }).unwrap();
+    }
+
     /// Sets up LiteSVM and loads both programs
     fn setup() -> (LiteSVM, Keypair) {
         let mut svm = LiteSVM::new();
@@ -48,6 +132,7 @@ mod tests {
         let vault_program_data = std::fs::read(vault_so_path)
             .expect("Failed to read vault program");
         svm.add_program(PROGRAM_ID, &vault_program_data);
+        set_upgrade_authority(&mut svm, &payer.pubkey());
 
         // Load hook program
         let hook_so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -117,19 +202,20 @@ mod tests {
         msg!("Mint created with TransferHook + 5% interest");
 
         // STEP 2: Initialize vault
-        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault"], &PROGRAM_ID);
+        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault", mint_keypair.pubkey().as_ref()], &PROGRAM_ID);
         let init_vault_ix = Instruction {
             program_id: PROGRAM_ID,
             accounts: crate::accounts::Initialize {
                 user: payer.pubkey(),
                 vault: vault_pda,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
                 token_program: spl_token_2022::ID,
                 system_program: SYSTEM_PROGRAM_ID,
+                program: PROGRAM_ID,
+                program_data: derive_program_data(),
             }
                 .to_account_metas(None),
-            data: crate::instruction::Initialize {}.data(),
+            data: crate::instruction::Initialize { allow_mutable_hook: true }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -160,7 +246,7 @@ mod tests {
 
         let blockhash = svm.latest_blockhash();
         svm.send_transaction(Transaction::new_signed_with_payer(
-            &[init_extra_ix],
+            &[initialize_hook_config_ix(&payer, &mint_keypair.pubkey()), init_extra_ix],
             Some(&payer.pubkey()),
             &[&payer],
             blockhash,
@@ -168,31 +254,9 @@ mod tests {
             .expect("ExtraAccountMetaList init failed");
         msg!("ExtraAccountMetaList initialized");
 
-        // STEP 4: Whitelist user
-        let (user_whitelist, _) = Pubkey::find_program_address(
-            &[b"hook", payer.pubkey().as_ref()],
-            &HOOK_PROGRAM_ID,
-        );
-
-        let whitelist_ix = Instruction {
-            program_id: HOOK_PROGRAM_ID,
-            accounts: hook::accounts::AddToWhitelist {
-                whitelist: user_whitelist,
-                authority: payer.pubkey(),
-                user: payer.pubkey(),
-                system_program: SYSTEM_PROGRAM_ID,
-            }
-                .to_account_metas(None),
-            data: hook::instruction::AddToWhitelist {}.data(),
-        };
-
-        let blockhash = svm.latest_blockhash();
-        svm.send_transaction(Transaction::new_signed_with_payer(
-            &[whitelist_ix],
-            Some(&payer.pubkey()),
-            &[&payer],
-            blockhash,
-        )).expect("Whitelist failed");
+        // STEP 4: Whitelist user and vault
+        whitelist(&mut svm, &payer, &mint_keypair.pubkey(), &payer.pubkey());
+        whitelist(&mut svm, &payer, &mint_keypair.pubkey(), &vault_pda);
         msg!("User whitelisted");
 
         // STEP 5: Create user token account and mint tokens
@@ -241,17 +305,18 @@ mod tests {
                 user: payer.pubkey(),
                 user_token_account,
                 vault: vault_pda,
+                user_position: position_pda(&vault_pda, &payer.pubkey()),
                 vault_token_account,
                 mint: mint_keypair.pubkey(),
-                hook_program: HOOK_PROGRAM_ID,
-                extra_account_meta_list,
-                user_whitelist,
                 token_program: spl_token_2022::ID,
                 associated_token_program: anchor_spl::associated_token::ID,
                 system_program: SYSTEM_PROGRAM_ID,
             }
-                .to_account_metas(None),
-            data: crate::instruction::Deposit { amount: initial_amount }.data(),
+                .to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(&mint_keypair.pubkey(), &[&payer.pubkey(), &vault_pda]))
+                .collect(),
+            data: crate::instruction::Deposit { amount: initial_amount, lock_duration: 0 }.data(),
         };
 
         let blockhash = svm.latest_blockhash();
@@ -352,4 +417,251 @@ mod tests {
         // msg!("ℹ️  Note: Base amount stays at {}, interest is UI-only", base_amount);
     }
 
+    const SECONDS_PER_YEAR: i64 = 31_556_736; // 365.24 days, as Token-2022 counts a year
+
+    fn set_clock(svm: &mut LiteSVM, unix_timestamp: i64) {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        svm.set_sysvar::<Clock>(&clock);
+        svm.expire_blockhash();
+    }
+
+    fn send(svm: &mut LiteSVM, signers: &[&Keypair], ixs: &[Instruction]) {
+        let blockhash = svm.latest_blockhash();
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&signers[0].pubkey()), signers, blockhash);
+        svm.send_transaction(tx).expect("Transaction failed");
+    }
+
+    /// Lets `user` both send and receive `mint`
+    fn whitelist(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        let whitelist = hook_whitelist_pda(mint, user);
+        let whitelist_ix = Instruction {
+            program_id: HOOK_PROGRAM_ID,
+            accounts: hook::accounts::AddToWhitelist {
+                whitelist,
+                counter: hook_counter_pda(mint, user),
+                admin: payer.pubkey(),
+                config: hook_config_pda(mint),
+                user: *user,
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None),
+            data: hook::instruction::AddToWhitelist { role: hook::Role::Both }.data(),
+        };
+        send(svm, &[payer], &[whitelist_ix]);
+        whitelist
+    }
+
+    /// Creates a hooked mint paying `rate` basis points a year and a vault for it
+    fn setup_interest_vault(svm: &mut LiteSVM, payer: &Keypair, rate: i16) -> (Pubkey, Pubkey) {
+        let mint_keypair = Keypair::new();
+        let mint = mint_keypair.pubkey();
+        let extensions = vec![ExtensionType::TransferHook, ExtensionType::InterestBearingConfig];
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions).unwrap();
+        let rent = svm.minimum_balance_for_rent_exemption(space);
+
+        send(svm, &[payer, &mint_keypair], &[
+            solana_system_interface::instruction::create_account(
+                &payer.pubkey(), &mint, rent, space as u64, &spl_token_2022::ID,
+            ),
+            transfer_hook::instruction::initialize(
+                &spl_token_2022::ID, &mint, Some(payer.pubkey()), Some(HOOK_PROGRAM_ID),
+            ).unwrap(),
+            spl_token_2022::extension::interest_bearing_mint::instruction::initialize(
+                &spl_token_2022::ID, &mint, Some(payer.pubkey()), rate,
+            ).unwrap(),
+            spl_token_2022::instruction::initialize_mint(&spl_token_2022::ID, &mint, &payer.pubkey(), None, 6).unwrap(),
+        ]);
+
+        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &PROGRAM_ID);
+        let (extra_account_meta_list, _) = Pubkey::find_program_address(
+            &[b"extra-account-metas", mint.as_ref()],
+            &HOOK_PROGRAM_ID,
+        );
+
+        send(svm, &[payer], &[
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts: crate::accounts::Initialize {
+                    user: payer.pubkey(),
+                    vault: vault_pda,
+                    mint,
+                    token_program: spl_token_2022::ID,
+                    system_program: SYSTEM_PROGRAM_ID,
+                    program: PROGRAM_ID,
+                    program_data: derive_program_data(),
+                }.to_account_metas(None),
+                data: crate::instruction::Initialize { allow_mutable_hook: true }.data(),
+            },
+            initialize_hook_config_ix(payer, &mint),
+            Instruction {
+                program_id: HOOK_PROGRAM_ID,
+                accounts: vec![
+                    solana_instruction::AccountMeta::new(payer.pubkey(), true),
+                    solana_instruction::AccountMeta::new(extra_account_meta_list, false),
+                    solana_instruction::AccountMeta::new_readonly(mint, false),
+                    solana_instruction::AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                ],
+                data: hook_program::instruction::InitializeExtraAccountMetaList {}.data(),
+            },
+        ]);
+        whitelist(svm, payer, &mint, &vault_pda);
+
+        (mint, vault_pda)
+    }
+
+    /// Creates a whitelisted user holding `amount` of `mint`
+    fn fund_user(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, amount: u64) -> (Keypair, Pubkey) {
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        whitelist(svm, payer, mint, &user.pubkey());
+
+        let token_account = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &user.pubkey(), mint, &spl_token_2022::ID,
+        );
+        send(svm, &[payer], &[
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer.pubkey(), &user.pubkey(), mint, &spl_token_2022::ID,
+            ),
+            spl_token_2022::instruction::mint_to(
+                &spl_token_2022::ID, mint, &token_account, &payer.pubkey(), &[], amount,
+            ).unwrap(),
+        ]);
+
+        (user, token_account)
+    }
+
+    fn vault_accounts(mint: &Pubkey) -> (Pubkey, Pubkey) {
+        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &PROGRAM_ID);
+        let vault_token_account = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &vault_pda, mint, &spl_token_2022::ID,
+        );
+        (vault_pda, vault_token_account)
+    }
+
+    fn deposit(svm: &mut LiteSVM, user: &Keypair, user_token_account: &Pubkey, mint: &Pubkey, amount: u64) {
+        let (vault_pda, vault_token_account) = vault_accounts(mint);
+        let deposit_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Deposit {
+                user: user.pubkey(),
+                user_token_account: *user_token_account,
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, &user.pubkey()),
+                vault_token_account,
+                mint: *mint,
+                token_program: spl_token_2022::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: SYSTEM_PROGRAM_ID,
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(mint, &[&user.pubkey(), &vault_pda]))
+                .collect(),
+            data: crate::instruction::Deposit { amount, lock_duration: 0 }.data(),
+        };
+        send(svm, &[user], &[deposit_ix]);
+    }
+
+    fn withdraw(svm: &mut LiteSVM, user: &Keypair, user_token_account: &Pubkey, mint: &Pubkey, shares: u64) {
+        let (vault_pda, vault_token_account) = vault_accounts(mint);
+        let withdraw_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::Withdraw {
+                user: user.pubkey(),
+                user_token_account: *user_token_account,
+                vault: vault_pda,
+                user_position: position_pda(&vault_pda, &user.pubkey()),
+                vault_token_account,
+                mint: *mint,
+                treasury_token_account: None,
+                token_program: spl_token_2022::ID,
+            }.to_account_metas(None)
+                .into_iter()
+                .chain(hook_accounts(mint, &[&vault_pda, &user.pubkey()]))
+                .collect(),
+            data: crate::instruction::Withdraw { shares, accept_penalty: false }.data(),
+        };
+        send(svm, &[user], &[withdraw_ix]);
+    }
+
+    fn shares_of(svm: &LiteSVM, mint: &Pubkey, user: &Pubkey) -> u64 {
+        let (vault_pda, _) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &PROGRAM_ID);
+        let account = svm.get_account(&position_pda(&vault_pda, user)).unwrap();
+        UserPosition::try_deserialize(&mut account.data.as_ref()).unwrap().shares
+    }
+
+    fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = svm.get_account(token_account).unwrap();
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data).unwrap().base.amount
+    }
+
+    /// UI amount of `amount` at the current clock, as Token-2022 displays it
+    fn ui_amount(svm: &LiteSVM, mint: &Pubkey, amount: u64) -> f64 {
+        let mint_account = svm.get_account(mint).unwrap();
+        let mint_data = StateWithExtensions::<MintState>::unpack(&mint_account.data).unwrap();
+        let config = mint_data.get_extension::<InterestBearingConfig>().unwrap();
+        let now = svm.get_sysvar::<Clock>().unix_timestamp;
+
+        config.amount_to_ui_amount(amount, mint_data.base.decimals, now)
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_early_depositor_earns_more_interest() {
+        let (mut svm, payer) = setup();
+        let start = 1_000_000;
+        set_clock(&mut svm, start);
+
+        // 5% a year
+        let (mint, vault_pda) = setup_interest_vault(&mut svm, &payer, 500);
+        let (user_a, user_a_tokens) = fund_user(&mut svm, &payer, &mint, 1_000_000);
+        let (user_b, user_b_tokens) = fund_user(&mut svm, &payer, &mint, 1_000_000);
+
+        // A deposits 1 token (UI) into the empty vault, one share per UI unit
+        let a_deposit_ui = ui_amount(&svm, &mint, 1_000_000);
+        deposit(&mut svm, &user_a, &user_a_tokens, &mint, 1_000_000);
+        assert_eq!(shares_of(&svm, &mint, &user_a.pubkey()), 1_000_000);
+
+        // A year later, 1 token (UI) is fewer raw units. B deposits that much
+        set_clock(&mut svm, start + SECONDS_PER_YEAR);
+        let b_amount = (1_000_000.0 / 0.05f64.exp()) as u64;
+        let b_deposit_ui = ui_amount(&svm, &mint, b_amount);
+        assert!((b_deposit_ui - a_deposit_ui).abs() < 0.000_01);
+        deposit(&mut svm, &user_b, &user_b_tokens, &mint, b_amount);
+
+        // A's shares grew with a year of interest, so the same UI amount buys B fewer
+        let b_shares = shares_of(&svm, &mint, &user_b.pubkey());
+        assert!(b_shares < 1_000_000);
+        assert!((b_shares as f64 - 1_000_000.0 / 0.05f64.exp()).abs() <= 2.0);
+        let vault_account = svm.get_account(&vault_pda).unwrap();
+        let vault_data = Vault::try_deserialize(&mut vault_account.data.as_ref()).unwrap();
+        assert_eq!(vault_data.total_shares, 1_000_000 + b_shares);
+        assert_eq!(vault_data.total_assets, 1_000_000 + b_amount);
+
+        // Another year on, both exit
+        set_clock(&mut svm, start + 2 * SECONDS_PER_YEAR);
+        withdraw(&mut svm, &user_a, &user_a_tokens, &mint, 1_000_000);
+        withdraw(&mut svm, &user_b, &user_b_tokens, &mint, b_shares);
+
+        let a_withdrawn = token_balance(&svm, &user_a_tokens);
+        let b_withdrawn = token_balance(&svm, &user_b_tokens) - (1_000_000 - b_amount);
+        let a_interest = ui_amount(&svm, &mint, a_withdrawn) - a_deposit_ui;
+        let b_interest = ui_amount(&svm, &mint, b_withdrawn) - b_deposit_ui;
+        msg!("A earned {:.6} UI over two years, B earned {:.6} UI over one", a_interest, b_interest);
+
+        // Both put in 1 UI, A earned two years of interest on it and B one
+        assert!(a_interest > b_interest);
+        assert!((a_interest - (0.1f64.exp() - 1.0)).abs() < 0.000_01);
+        assert!((b_interest - (0.05f64.exp() - 1.0)).abs() < 0.000_01);
+
+        // Nothing is left behind
+        let vault_token_account = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+            &vault_pda, &mint, &spl_token_2022::ID,
+        );
+        assert_eq!(token_balance(&svm, &vault_token_account), 0);
+        assert_eq!(a_withdrawn + b_withdrawn, 1_000_000 + b_amount);
+        assert_eq!(shares_of(&svm, &mint, &user_a.pubkey()), 0);
+        assert_eq!(shares_of(&svm, &mint, &user_b.pubkey()), 0);
+    }
 }
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+    },
+    token_interface::{transfer_checked, Mint, TokenInterface, TransferChecked},
+};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address,
+    onchain::add_extra_accounts_for_execute_cpi,
+};
+use crate::VaultError;
+
+/// TransferHook extension of a Token-2022 mint, if it has one
+pub fn transfer_hook_extension(mint: &AccountInfo) -> Result<Option<TransferHook>> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    Ok(mint_state.get_extension::<TransferHook>().ok().copied())
+}
+
+/// Transfer hook program configured on a Token-2022 mint, if any
+pub fn transfer_hook_program_id(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    Ok(transfer_hook_extension(mint)?.and_then(|hook| Option::<Pubkey>::from(hook.program_id)))
+}
+
+/// Fails if the mint's hook program is no longer the one the vault was set up with
+pub fn require_hook_program(mint: &AccountInfo, expected: Option<Pubkey>) -> Result<()> {
+    require!(transfer_hook_program_id(mint)? == expected, VaultError::HookProgramChanged);
+    Ok(())
+}
+
+/// `transfer_checked` that works with any transfer hook, or none.
+///
+/// For a hooked mint, `remaining_accounts` must hold the hook program named by
+/// the mint's TransferHook extension, its ExtraAccountMetaList and every
+/// account the list resolves to, in any order. They are ignored otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+
+    let Some(hook_program_id) = transfer_hook_program_id(&mint_info)? else {
+        let cpi_accounts = TransferChecked {
+            from,
+            to,
+            authority,
+            mint: mint_info,
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        return transfer_checked(cpi_ctx, amount, mint.decimals);
+    };
+
+    // The hook program and its account list must be the ones the mint points at
+    let hook_program = remaining_accounts
+        .iter()
+        .find(|account| account.key == &hook_program_id)
+        .ok_or(VaultError::HookProgramMismatch)?;
+    require!(hook_program.executable, VaultError::HookProgramMismatch);
+
+    let extra_account_meta_list = get_extra_account_metas_address(mint_info.key, &hook_program_id);
+    require!(
+        remaining_accounts.iter().any(|account| account.key == &extra_account_meta_list),
+        VaultError::MissingExtraAccountMetas
+    );
+
+    // Build the base transfer_checked instruction
+    let mut transfer_ix = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        from.key,
+        mint_info.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        mint.decimals,
+    )?;
+
+    let mut cpi_account_infos = vec![
+        from.clone(),
+        mint_info.clone(),
+        to.clone(),
+        authority.clone(),
+        token_program.to_account_info(),
+    ];
+
+    // Resolve the rest from the ExtraAccountMetaList
+    add_extra_accounts_for_execute_cpi(
+        &mut transfer_ix,
+        &mut cpi_account_infos,
+        &hook_program_id,
+        from,
+        mint_info,
+        to,
+        authority,
+        amount,
+        remaining_accounts,
+    )?;
+
+    invoke_signed(&transfer_ix, &cpi_account_infos, signer_seeds)?;
+
+    Ok(())
+}